@@ -0,0 +1,15 @@
+-- Add migration script here
+alter table animes add column main_title varchar(1024) not null default '';
+
+update animes a
+set a.main_title = coalesce((select jt.name
+                             from json_table(a.titles, '$[*]' columns (
+                                 name varchar(1024) path '$.name',
+                                 is_main bool path '$.is_main'
+                                 )) jt
+                             where jt.is_main
+                             limit 1), '');
+
+create index animes_main_title_idx on animes(main_title, id);
+create index animes_release_date_idx on animes(release_date, id);
+create index animes_created_at_idx on animes(created_at, id);
@@ -0,0 +1,6 @@
+-- Add migration script here
+alter table animes add column synopsis_search varchar(4000);
+
+create fulltext index animes_title_search_ft_idx on animes(title_search);
+create fulltext index animes_synopsis_search_ft_idx on animes(synopsis_search);
+create fulltext index animes_search_ft_idx on animes(title_search, synopsis_search);
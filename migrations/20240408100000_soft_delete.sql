@@ -0,0 +1,5 @@
+-- Add migration script here
+alter table animes add column deleted_at timestamp null default null;
+alter table seasons add column deleted_at timestamp null default null;
+alter table sources add column deleted_at timestamp null default null;
+alter table episodes add column deleted_at timestamp null default null;
@@ -0,0 +1,15 @@
+-- Add migration script here
+create table audit_events (
+    id bigint unsigned auto_increment primary key,
+    user_id varchar(36) not null,
+    rpc_name varchar(64) not null,
+    entity_type tinyint unsigned not null,
+    entity_id varchar(36) not null,
+    diff json not null,
+    created_at timestamp not null,
+    foreign key (user_id) references users(id)
+);
+
+create index audit_events_user_idx on audit_events(user_id, id);
+create index audit_events_entity_idx on audit_events(entity_type, entity_id, id);
+create index audit_events_created_at_idx on audit_events(created_at);
@@ -0,0 +1,13 @@
+-- Add migration script here
+create table anime_revisions (
+    id bigint unsigned auto_increment primary key,
+    anime_id int unsigned not null,
+    revision int unsigned not null,
+    snapshot json not null,
+    created_by varchar(36) not null,
+    created_at timestamp not null,
+    reverted_from int unsigned,
+    foreign key (anime_id) references animes(id) on delete cascade,
+    foreign key (created_by) references users(id),
+    unique (anime_id, revision)
+);
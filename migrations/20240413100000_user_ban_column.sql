@@ -0,0 +1,4 @@
+-- Add migration script here
+alter table users add column is_banned bool not null default false;
+
+create index users_display_name_idx on users(display_name, id);
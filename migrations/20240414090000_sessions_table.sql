@@ -0,0 +1,11 @@
+-- Add migration script here
+create table sessions (
+    id varchar(36) not null primary key,
+    user_id varchar(36) not null,
+    created_at timestamp not null,
+    expires_at timestamp not null,
+    revoked_at timestamp null,
+    foreign key (user_id) references users(id) on delete cascade
+);
+
+create index sessions_user_idx on sessions(user_id, expires_at);
@@ -0,0 +1,11 @@
+-- Add migration script here
+create table refresh_tokens (
+    id varchar(36) not null primary key,
+    session_id varchar(36) not null,
+    token_hash char(64) not null unique,
+    created_at timestamp not null,
+    expires_at timestamp not null,
+    used_at timestamp null,
+    revoked_at timestamp null,
+    foreign key (session_id) references sessions(id) on delete cascade
+);
@@ -0,0 +1,7 @@
+-- Add migration script here
+alter table users modify column recovery_key varchar(255);
+alter table users add column recovery_key_prefix varchar(16);
+
+update users set recovery_key = null;
+
+create unique index users_recovery_key_prefix_idx on users(recovery_key_prefix);
@@ -0,0 +1,9 @@
+-- Add migration script here
+create table user_credentials (
+    user_id varchar(36) not null primary key,
+    email varchar(255) not null unique,
+    password_hash varchar(255) not null,
+    created_at timestamp not null,
+    updated_at timestamp not null,
+    foreign key (user_id) references users(id) on delete cascade
+);
@@ -0,0 +1,3 @@
+-- Add migration script here
+alter table users add column mal_profile varchar(255);
+alter table users add column anilist_profile varchar(255);
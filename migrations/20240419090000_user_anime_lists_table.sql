@@ -0,0 +1,15 @@
+-- Add migration script here
+create table user_anime_lists (
+    user_id varchar(36) not null,
+    anime_id int unsigned not null,
+    status tinyint unsigned not null,
+    score tinyint unsigned,
+    progress int unsigned,
+    created_at timestamp not null,
+    updated_at timestamp not null,
+    primary key (user_id, anime_id),
+    foreign key (user_id) references users(id) on delete cascade,
+    foreign key (anime_id) references animes(id) on delete cascade
+);
+
+create index user_anime_lists_status_idx on user_anime_lists(user_id, status, updated_at);
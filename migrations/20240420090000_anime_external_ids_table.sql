@@ -0,0 +1,31 @@
+-- Add migration script here
+create table anime_external_ids (
+    anime_list tinyint unsigned not null,
+    id_in_list varchar(100) not null,
+    anime_id int unsigned not null,
+    primary key (anime_list, id_in_list),
+    foreign key (anime_id) references animes(id) on delete cascade
+);
+
+create index anime_external_ids_anime_id_idx on anime_external_ids(anime_id);
+
+-- An external id listed by more than one anime goes to the live anime with the lowest id.
+-- The others keep it in anime_in_lists and are reported on startup until an admin fixes them.
+insert into anime_external_ids (anime_list, id_in_list, anime_id)
+select ranked.anime_list, ranked.id_in_list, ranked.anime_id
+from (
+    select case entries.anime_list when 'MyAnimeList' then 0 when 'AniList' then 1 end as anime_list,
+           entries.id_in_list,
+           animes.id as anime_id,
+           row_number() over (
+               partition by entries.anime_list, entries.id_in_list
+               order by animes.deleted_at is not null, animes.id
+           ) as position
+    from animes,
+         json_table(animes.anime_in_lists, '$[*]' columns (
+             anime_list varchar(20) path '$.anime_list',
+             id_in_list varchar(100) path '$.id_in_list'
+         )) as entries
+    where entries.anime_list in ('MyAnimeList', 'AniList')
+) as ranked
+where ranked.position = 1;
@@ -0,0 +1,13 @@
+-- Add migration script here
+create table watch_progress (
+    user_id varchar(36) not null,
+    episode_id varchar(32) not null,
+    position_seconds int unsigned not null,
+    completed bool not null,
+    updated_at timestamp not null,
+    primary key (user_id, episode_id),
+    foreign key (user_id) references users(id) on delete cascade,
+    foreign key (episode_id) references episodes(id) on delete cascade
+);
+
+create index watch_progress_user_updated_idx on watch_progress(user_id, updated_at);
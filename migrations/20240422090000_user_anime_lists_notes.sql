@@ -0,0 +1,2 @@
+-- Add migration script here
+alter table user_anime_lists add column notes varchar(2000);
@@ -0,0 +1,11 @@
+-- Add migration script here
+alter table animes
+    add column score_sum int unsigned not null default 0,
+    add column score_count int unsigned not null default 0,
+    add column average_score double generated always as (if(score_count = 0, 0, score_sum / score_count)) stored not null;
+
+update animes
+set score_sum = (select coalesce(sum(score), 0) from user_anime_lists where user_anime_lists.anime_id = animes.id),
+    score_count = (select count(score) from user_anime_lists where user_anime_lists.anime_id = animes.id);
+
+create index animes_average_score_idx on animes(average_score, id);
@@ -0,0 +1,22 @@
+-- Add migration script here
+create table comments (
+    id bigint unsigned auto_increment primary key,
+    episode_id varchar(32),
+    anime_id int unsigned,
+    parent_id bigint unsigned,
+    user_id varchar(36) not null,
+    content varchar(4000) not null,
+    is_spoiler bool not null,
+    is_hidden bool not null default false,
+    is_locked bool not null default false,
+    created_at timestamp not null,
+    updated_at timestamp null,
+    deleted_at timestamp null,
+    foreign key (episode_id) references episodes(id) on delete cascade,
+    foreign key (anime_id) references animes(id) on delete cascade,
+    foreign key (parent_id) references comments(id) on delete cascade,
+    foreign key (user_id) references users(id) on delete cascade
+);
+
+create index comments_episode_idx on comments(episode_id, parent_id, id);
+create index comments_anime_idx on comments(anime_id, parent_id, id);
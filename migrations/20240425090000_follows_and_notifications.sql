@@ -0,0 +1,26 @@
+-- Add migration script here
+create table anime_follows (
+    user_id varchar(36) not null,
+    anime_id int unsigned not null,
+    created_at timestamp not null,
+    primary key (user_id, anime_id),
+    foreign key (user_id) references users(id) on delete cascade,
+    foreign key (anime_id) references animes(id) on delete cascade
+);
+
+create index anime_follows_anime_idx on anime_follows(anime_id);
+
+create table notifications (
+    id bigint unsigned auto_increment primary key,
+    user_id varchar(36) not null,
+    anime_id int unsigned not null,
+    episode_id varchar(32) not null,
+    created_at timestamp not null,
+    read_at timestamp null,
+    unique (user_id, episode_id),
+    foreign key (user_id) references users(id) on delete cascade,
+    foreign key (anime_id) references animes(id) on delete cascade,
+    foreign key (episode_id) references episodes(id) on delete cascade
+);
+
+create index notifications_user_idx on notifications(user_id, id);
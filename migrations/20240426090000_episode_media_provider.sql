@@ -0,0 +1,3 @@
+-- Add migration script here
+alter table episodes rename column lbry_media_id to media_id;
+alter table episodes add column media_provider tinyint unsigned not null default 0;
@@ -0,0 +1,6 @@
+-- Add migration script here
+alter table episodes add column duration_seconds int unsigned null;
+alter table episodes add column width smallint unsigned null;
+alter table episodes add column height smallint unsigned null;
+alter table episodes add column thumbnail_url varchar(500) null;
+alter table episodes add column upload_date datetime null;
@@ -0,0 +1,12 @@
+-- Add migration script here
+create table episode_health_checks (
+    episode_id varchar(32) not null primary key,
+    is_healthy bool not null,
+    last_error varchar(500),
+    consecutive_failures int unsigned not null default 0,
+    auto_hidden bool not null default false,
+    checked_at datetime not null,
+    foreign key (episode_id) references episodes(id) on delete cascade,
+    index (is_healthy, consecutive_failures),
+    index (checked_at)
+);
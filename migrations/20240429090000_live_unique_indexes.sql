@@ -0,0 +1,12 @@
+-- Add migration script here
+alter table seasons add column is_live bool generated always as (if(deleted_at is null, true, null)) stored;
+create unique index seasons_live_sequence_idx on seasons(anime_id, sequence, is_live);
+drop index sequence_unique_idx on seasons;
+
+alter table sources add column is_live bool generated always as (if(deleted_at is null, true, null)) stored;
+create unique index sources_live_name_source_type_idx on sources(name, source_type, is_live);
+drop index name_source_type_index on sources;
+
+alter table episodes add column is_live bool generated always as (if(deleted_at is null, true, null)) stored;
+create unique index episodes_live_season_source_sequence_idx on episodes(season_id, source_id, sequence, is_live);
+drop index episodes_anime_source_sequence_idx on episodes;
@@ -0,0 +1,7 @@
+-- Add migration script here
+create table legacy_token_exchanges (
+    token_hash char(64) not null primary key,
+    user_id varchar(36) not null,
+    exchanged_at timestamp not null,
+    foreign key (user_id) references users(id) on delete cascade
+);
@@ -0,0 +1,2 @@
+-- Add migration script here
+create index anime_follows_user_created_idx on anime_follows(user_id, created_at, anime_id);
@@ -1,15 +1,24 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use crate::models::config::Config;
 use crate::models::error::ApplicationError;
 use crate::models::user::User;
+use crate::repositories::{session_repository, user_repository, DatabaseConnection};
 use tonic::Request;
 
+const DEFAULT_AUTH_CACHE_TTL_SECONDS: u64 = 30;
+
+#[tonic::async_trait]
 pub trait Authentication {
     fn get_auth_token(&self) -> Option<String>;
-    fn get_user(&self, config: &Config) -> Result<User, ApplicationError>;
+    async fn get_user(&self, cache: &AuthenticationCache) -> Result<User, ApplicationError>;
     // fn is_authenticated(&self, config: &Config) -> bool;
 }
 
-impl<T> Authentication for Request<T> {
+#[tonic::async_trait]
+impl<T: Send + Sync> Authentication for Request<T> {
     fn get_auth_token(&self) -> Option<String> {
         let metadata = self.metadata().clone();
         let headers = metadata.into_headers();
@@ -23,12 +32,11 @@ impl<T> Authentication for Request<T> {
         Some(auth_header)
     }
 
-    fn get_user(&self, config: &Config) -> Result<User, ApplicationError> {
+    async fn get_user(&self, cache: &AuthenticationCache) -> Result<User, ApplicationError> {
         let token = self
             .get_auth_token()
             .ok_or(ApplicationError::Unauthorized)?;
-        let user = User::from_token(token, config)?;
-        Ok(user)
+        cache.get_user(token).await
     }
 
     // fn is_authenticated(&self, config: &Config) -> bool {
@@ -37,6 +45,158 @@ impl<T> Authentication for Request<T> {
     // }
 }
 
+pub trait ClientAddress {
+    fn get_client_address(&self, config: &Config) -> Result<String, ApplicationError>;
+}
+
+impl<T> ClientAddress for Request<T> {
+    /// Behind a reverse proxy every peer address is the proxy's, so the configured header
+    /// is trusted instead. The last entry is used since it is the one the proxy appended.
+    fn get_client_address(&self, config: &Config) -> Result<String, ApplicationError> {
+        if let Some(header) = &config.client_address_header {
+            return self
+                .metadata()
+                .get(header.as_str())
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').next())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .ok_or(ApplicationError::Unauthorized);
+        }
+
+        self.remote_addr()
+            .map(|addr| addr.ip().to_string())
+            .ok_or(ApplicationError::Unauthorized)
+    }
+}
+
+struct CachedUser {
+    user: User,
+    cached_at: Instant,
+}
+
+pub struct AuthenticationCache {
+    config: Arc<Config>,
+    database_connection: Arc<DatabaseConnection>,
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CachedUser>>,
+}
+
+impl AuthenticationCache {
+    pub fn new(config: Arc<Config>, database_connection: Arc<DatabaseConnection>) -> Self {
+        let ttl = config
+            .auth_cache_ttl_seconds
+            .unwrap_or(DEFAULT_AUTH_CACHE_TTL_SECONDS);
+
+        Self {
+            config,
+            database_connection,
+            ttl: Duration::from_secs(ttl),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_user(&self, token: String) -> Result<User, ApplicationError> {
+        let token_user = User::from_token(token, &self.config)?;
+        let session_id = token_user
+            .session_id
+            .clone()
+            .ok_or(ApplicationError::Unauthorized)?;
+
+        if let Some(user) = self.get_cached(&session_id) {
+            return Ok(user);
+        }
+
+        let conn = &self.database_connection;
+        let session = session_repository::session_get_by_id(conn, &session_id)
+            .await
+            .map_err(|_| ApplicationError::Unauthorized)?;
+        if !session.is_active() || session.user_id != token_user.id {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let mut user = user_repository::user_get_by_id(conn, &session.user_id)
+            .await
+            .map_err(|_| ApplicationError::Unauthorized)?;
+        if user.is_banned {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        user.session_id = Some(session_id.clone());
+        self.insert(session_id, user.clone());
+
+        Ok(user)
+    }
+
+    pub fn evict_session(&self, session_id: &str) {
+        self.lock().remove(session_id);
+    }
+
+    pub fn evict_user(&self, user_id: &str) {
+        self.lock().retain(|_, cached| cached.user.id != user_id);
+    }
+
+    fn get_cached(&self, session_id: &str) -> Option<User> {
+        self.lock()
+            .get(session_id)
+            .filter(|cached| cached.cached_at.elapsed() < self.ttl)
+            .map(|cached| cached.user.clone())
+    }
+
+    fn insert(&self, session_id: String, user: User) {
+        let mut entries = self.lock();
+        entries.retain(|_, cached| cached.cached_at.elapsed() < self.ttl);
+        entries.insert(
+            session_id,
+            CachedUser {
+                user,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedUser>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub struct RateLimiter {
+    max_attempts: u32,
+    window: Duration,
+    attempts: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub fn new(max_attempts: u32, window: Duration) -> Self {
+        Self {
+            max_attempts,
+            window,
+            attempts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn check(&self, key: &str) -> Result<(), ApplicationError> {
+        let mut attempts = self
+            .attempts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        attempts.retain(|_, (started_at, _)| started_at.elapsed() < self.window);
+
+        let (_, count) = attempts
+            .entry(key.to_string())
+            .or_insert((Instant::now(), 0));
+        if *count >= self.max_attempts {
+            return Err(ApplicationError::TooManyRequests);
+        }
+        *count += 1;
+
+        Ok(())
+    }
+}
+
 pub trait OptionToAppResult<T> {
     fn ok_or_app_result(self, message: &'static str) -> Result<T, ApplicationError>;
 }
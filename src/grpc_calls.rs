@@ -1,41 +1,107 @@
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
+use tokio_stream::{Stream, StreamExt};
 use tonic::{Request, Response, Status};
 
 use crate::arkalis_service::arkalis_core_service_server::ArkalisCoreService;
 use crate::arkalis_service::{
-    AddSeasonRequest, AddSeasonResponse, CreateAdminRequest, CreateAdminResponse,
-    CreateAnimeRequest, CreateAnimeResponse, CreateEpisodeRequest, CreateEpisodeResponse,
-    CreateRecoveryKeyRequest, CreateRecoveryKeyResponse, CreateSourceRequest, CreateSourceResponse,
-    CreateTokenRequest, CreateTokenResponse, EditAnimeRequest, EditAnimeResponse,
-    EditSeasonRequest, EditSeasonResponse, EditSourceRequest, EditSourceResponse,
-    GetAnimeByIdRequest, GetAnimeByIdResponse, GetAnimeSeasonsRequest, GetAnimeSeasonsResponse,
-    GetEpisodeByIdRequest, GetEpisodeByIdResponse, GetEpisodesBySeasonAndSourceRequest,
-    GetEpisodesBySeasonAndSourceResponse, GetLastSeasonSequenceRequest,
-    GetLastSeasonSequenceResponse, GetSourceByIdRequest, GetSourceByIdResponse,
-    GetSourcesBySeasonIdRequest, GetSourcesBySeasonIdResponse, GetSourcesRequest,
-    GetSourcesResponse, GetUserInfoRequest, GetUserInfoResponse, RecoveryUserRequest,
-    RecoveryUserResponse, SearchAnimeRequest, SearchAnimeResponse, UpdateEpisodeRequest,
-    UpdateEpisodeResponse,
+    AddSeasonRequest, AddSeasonResponse, Anime, ChangePasswordRequest, ChangePasswordResponse,
+    ClearNotificationsRequest, ClearNotificationsResponse, CreateAdminRequest, CreateAdminResponse,
+    CreateAnimeRequest, CreateAnimeResponse, CreateCommentRequest, CreateCommentResponse,
+    CreateEpisodeRequest, CreateEpisodeResponse, CreateRecoveryKeyRequest,
+    CreateRecoveryKeyResponse, CreateSourceRequest, CreateSourceResponse, CreateTokenRequest,
+    CreateTokenResponse, DeleteAnimeRatingRequest, DeleteAnimeRatingResponse, DeleteAnimeRequest,
+    DeleteAnimeResponse, DeleteCommentRequest, DeleteCommentResponse, DeleteEpisodeRequest,
+    DeleteEpisodeResponse, DeleteSeasonRequest, DeleteSeasonResponse, DeleteSourceRequest,
+    DeleteSourceResponse, EditAnimeRequest, EditAnimeResponse, EditCommentRequest,
+    EditCommentResponse, EditSeasonRequest, EditSeasonResponse, EditSourceRequest,
+    EditSourceResponse, Episode, ExchangeLegacyTokenRequest, ExchangeLegacyTokenResponse,
+    FollowAnimeRequest, FollowAnimeResponse, GetAnimeByExternalIdRequest,
+    GetAnimeByExternalIdResponse, GetAnimeByIdRequest, GetAnimeByIdResponse, GetAnimeRatingRequest,
+    GetAnimeRatingResponse, GetAnimeRevisionRequest, GetAnimeRevisionResponse,
+    GetAnimeSeasonsRequest, GetAnimeSeasonsResponse, GetContinueWatchingRequest,
+    GetContinueWatchingResponse, GetEpisodeByIdRequest, GetEpisodeByIdResponse,
+    GetEpisodesBySeasonAndSourceRequest, GetEpisodesBySeasonAndSourceResponse,
+    GetLastSeasonSequenceRequest, GetLastSeasonSequenceResponse, GetSourceByIdRequest,
+    GetSourceByIdResponse, GetSourcesBySeasonIdRequest, GetSourcesBySeasonIdResponse,
+    GetSourcesRequest, GetSourcesResponse, GetUserInfoRequest, GetUserInfoResponse,
+    ImportAnimeRequest, ImportAnimeResponse, ImportWatchStatusRequest, ImportWatchStatusResponse,
+    LinkCredentialsRequest, LinkCredentialsResponse, ListAnimeListEntriesRequest,
+    ListAnimeListEntriesResponse, ListAnimeRevisionsRequest, ListAnimeRevisionsResponse,
+    ListAuditEventsRequest, ListAuditEventsResponse, ListBrokenEpisodesRequest,
+    ListBrokenEpisodesResponse, ListCommentsRequest, ListCommentsResponse,
+    ListFollowedAnimesRequest, ListFollowedAnimesResponse, ListNotificationsRequest,
+    ListNotificationsResponse, ListSessionsRequest, ListSessionsResponse, ListUsersRequest,
+    ListUsersResponse, LoginRequest, LoginResponse, MarkNotificationsReadRequest,
+    MarkNotificationsReadResponse, MarkSeasonWatchedRequest, MarkSeasonWatchedResponse,
+    ModerateCommentRequest, ModerateCommentResponse, PurgeEntityRequest, PurgeEntityResponse,
+    RateAnimeRequest, RateAnimeResponse, RecoveryUserRequest, RecoveryUserResponse,
+    RefreshTokenRequest, RefreshTokenResponse, RemoveAnimeListEntryRequest,
+    RemoveAnimeListEntryResponse, RenameUserRequest, RenameUserResponse,
+    ReportWatchProgressRequest, ReportWatchProgressResponse, RestoreEntityRequest,
+    RestoreEntityResponse, RevertAnimeRequest, RevertAnimeResponse, RevokeAllSessionsRequest,
+    RevokeAllSessionsResponse, RevokeSessionRequest, RevokeSessionResponse,
+    RotateRecoveryKeyRequest, RotateRecoveryKeyResponse, SearchAnimeRequest, SearchAnimeResponse,
+    Season, SetAnimeListEntryRequest, SetAnimeListEntryResponse, SetUserBanRequest,
+    SetUserBanResponse, SetUserRoleRequest, SetUserRoleResponse, Sources, UnfollowAnimeRequest,
+    UnfollowAnimeResponse, UpdateEpisodeRequest, UpdateEpisodeResponse, UpdateProfileRequest,
+    UpdateProfileResponse,
 };
-use crate::extensions::Authentication;
+use crate::extensions::{Authentication, AuthenticationCache, ClientAddress, RateLimiter};
 use crate::models::config::Config;
 use crate::models::error::ApplicationError;
 use crate::repositories::DatabaseConnection;
+use crate::services::anime_list_service::AnimeListService;
 use crate::services::anime_service::AnimeService;
+use crate::services::audit_service::AuditService;
+use crate::services::comment_service::CommentService;
 use crate::services::episode_service::EpisodeService;
+use crate::services::media_health_service::MediaHealthService;
+use crate::services::notification_service::NotificationService;
+use crate::services::rating_service::RatingService;
 use crate::services::season_service::SeasonService;
 use crate::services::source_service::SourceService;
+use crate::services::trash_service::TrashService;
 use crate::services::user_service::UserService;
+use crate::services::watch_progress_service::WatchProgressService;
+
+const CLIENT_MAX_ATTEMPTS: u32 = 30;
+const CLIENT_WINDOW_SECONDS: u64 = 60;
+const RECOVERY_MAX_ATTEMPTS: u32 = 5;
+const RECOVERY_WINDOW_SECONDS: u64 = 60;
+const LOGIN_MAX_ATTEMPTS: u32 = 10;
+const LOGIN_WINDOW_SECONDS: u64 = 60;
+
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+fn to_response_stream<T, S>(stream: S) -> ResponseStream<T>
+where
+    S: Stream<Item = Result<T, ApplicationError>> + Send + 'static,
+{
+    // The closure returns `Result<T, Status>`, and `Status` is what trips the lint.
+    #[allow(clippy::result_large_err)]
+    let stream = stream.map(|item| item.map_err(Status::from));
+    Box::pin(stream)
+}
 
 pub struct ArkalisGrpcServerServices {
     user_service: UserService,
-    config: Arc<Config>,
+    authentication_cache: Arc<AuthenticationCache>,
     anime_service: AnimeService,
     database_connection: Arc<DatabaseConnection>,
     season_service: SeasonService,
     source_service: SourceService,
     episode_service: EpisodeService,
+    trash_service: TrashService,
+    audit_service: AuditService,
+    anime_list_service: AnimeListService,
+    watch_progress_service: WatchProgressService,
+    rating_service: RatingService,
+    comment_service: CommentService,
+    notification_service: NotificationService,
+    media_health_service: MediaHealthService,
 }
 
 impl ArkalisGrpcServerServices {
@@ -43,14 +109,32 @@ impl ArkalisGrpcServerServices {
         let config = Arc::new(config);
         let database_connection = DatabaseConnection::new(&config).await;
         let database_connection = Arc::new(database_connection);
+        let authentication_cache = Arc::new(AuthenticationCache::new(
+            config.clone(),
+            database_connection.clone(),
+        ));
 
         ArkalisGrpcServerServices {
-            config: config.clone(),
+            authentication_cache: authentication_cache.clone(),
             user_service: UserService {
-                config,
+                config: config.clone(),
                 database_connection: database_connection.clone(),
+                authentication_cache,
+                client_rate_limiter: RateLimiter::new(
+                    CLIENT_MAX_ATTEMPTS,
+                    Duration::from_secs(CLIENT_WINDOW_SECONDS),
+                ),
+                recovery_rate_limiter: RateLimiter::new(
+                    RECOVERY_MAX_ATTEMPTS,
+                    Duration::from_secs(RECOVERY_WINDOW_SECONDS),
+                ),
+                login_rate_limiter: RateLimiter::new(
+                    LOGIN_MAX_ATTEMPTS,
+                    Duration::from_secs(LOGIN_WINDOW_SECONDS),
+                ),
             },
             anime_service: AnimeService {
+                config: config.clone(),
                 database_connection: database_connection.clone(),
             },
             database_connection: database_connection.clone(),
@@ -61,13 +145,43 @@ impl ArkalisGrpcServerServices {
                 database_connection: database_connection.clone(),
             },
             episode_service: EpisodeService {
+                config: config.clone(),
+                database_connection: database_connection.clone(),
+            },
+            trash_service: TrashService {
+                database_connection: database_connection.clone(),
+            },
+            audit_service: AuditService {
+                database_connection: database_connection.clone(),
+            },
+            anime_list_service: AnimeListService {
+                database_connection: database_connection.clone(),
+            },
+            watch_progress_service: WatchProgressService {
+                database_connection: database_connection.clone(),
+            },
+            rating_service: RatingService {
+                database_connection: database_connection.clone(),
+            },
+            comment_service: CommentService {
+                database_connection: database_connection.clone(),
+            },
+            notification_service: NotificationService {
+                database_connection: database_connection.clone(),
+            },
+            media_health_service: MediaHealthService {
+                config,
                 database_connection,
             },
         }
     }
 
     pub async fn startup_routine(&self) -> Result<(), ApplicationError> {
-        self.database_connection.migrate_database().await
+        self.database_connection.migrate_database().await?;
+        self.anime_service.reindex_search().await?;
+        self.anime_service.report_external_id_conflicts().await?;
+        self.media_health_service.start();
+        Ok(())
     }
 }
 
@@ -99,15 +213,16 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<GetUserInfoRequest>,
     ) -> Result<Response<GetUserInfoResponse>, Status> {
-        let user = request.get_user(&self.config)?;
-        Ok(Response::new(user.into()))
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self.user_service.get_user_info(user).await?;
+        Ok(Response::new(response))
     }
 
     async fn create_anime(
         &self,
         request: Request<CreateAnimeRequest>,
     ) -> Result<Response<CreateAnimeResponse>, Status> {
-        let user = request.get_user(&self.config)?;
+        let user = request.get_user(&self.authentication_cache).await?;
         let response = self
             .anime_service
             .add_anime(request.into_inner(), &user)
@@ -119,7 +234,7 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<CreateRecoveryKeyRequest>,
     ) -> Result<Response<CreateRecoveryKeyResponse>, Status> {
-        let user = request.get_user(&self.config)?;
+        let user = request.get_user(&self.authentication_cache).await?;
         let response = self.user_service.get_recovery_key(user).await?;
         Ok(Response::new(response))
     }
@@ -128,9 +243,10 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<RecoveryUserRequest>,
     ) -> Result<Response<RecoveryUserResponse>, Status> {
+        let client = request.get_client_address(&self.user_service.config)?;
         let response = self
             .user_service
-            .recovery_user(request.into_inner())
+            .recovery_user(request.into_inner(), &client)
             .await?;
         Ok(Response::new(response))
     }
@@ -139,7 +255,7 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<GetAnimeByIdRequest>,
     ) -> Result<Response<GetAnimeByIdResponse>, Status> {
-        let user = request.get_user(&self.config).ok();
+        let user = request.get_user(&self.authentication_cache).await.ok();
         let anime = self
             .anime_service
             .get_anime(request.into_inner(), &user)
@@ -151,7 +267,7 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<SearchAnimeRequest>,
     ) -> Result<Response<SearchAnimeResponse>, Status> {
-        let user = request.get_user(&self.config).ok();
+        let user = request.get_user(&self.authentication_cache).await.ok();
         let animes = self
             .anime_service
             .search_anime(request.into_inner(), &user)
@@ -163,7 +279,7 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<EditAnimeRequest>,
     ) -> Result<Response<EditAnimeResponse>, Status> {
-        let user = request.get_user(&self.config)?;
+        let user = request.get_user(&self.authentication_cache).await?;
         let response = self
             .anime_service
             .update_anime(request.into_inner(), &user)
@@ -175,7 +291,7 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<AddSeasonRequest>,
     ) -> Result<Response<AddSeasonResponse>, Status> {
-        let user = request.get_user(&self.config)?;
+        let user = request.get_user(&self.authentication_cache).await?;
         let response = self
             .season_service
             .add_season(request.into_inner(), &user)
@@ -209,7 +325,7 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<EditSeasonRequest>,
     ) -> Result<Response<EditSeasonResponse>, Status> {
-        let user = request.get_user(&self.config)?;
+        let user = request.get_user(&self.authentication_cache).await?;
         let response = self
             .season_service
             .update_season(request.into_inner(), &user)
@@ -221,7 +337,7 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<CreateSourceRequest>,
     ) -> Result<Response<CreateSourceResponse>, Status> {
-        let user = request.get_user(&self.config)?;
+        let user = request.get_user(&self.authentication_cache).await?;
         let response = self
             .source_service
             .add_source(request.into_inner(), &user)
@@ -244,7 +360,7 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<EditSourceRequest>,
     ) -> Result<Response<EditSourceResponse>, Status> {
-        let user = request.get_user(&self.config)?;
+        let user = request.get_user(&self.authentication_cache).await?;
         let response = self
             .source_service
             .update_source(request.into_inner(), &user)
@@ -267,7 +383,7 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<CreateEpisodeRequest>,
     ) -> Result<Response<CreateEpisodeResponse>, Status> {
-        let user = request.get_user(&self.config)?;
+        let user = request.get_user(&self.authentication_cache).await?;
         let response = self
             .episode_service
             .add_episode(request.into_inner(), &user)
@@ -279,7 +395,7 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
         &self,
         request: Request<UpdateEpisodeRequest>,
     ) -> Result<Response<UpdateEpisodeResponse>, Status> {
-        let user = request.get_user(&self.config)?;
+        let user = request.get_user(&self.authentication_cache).await?;
         let response = self
             .episode_service
             .update_episode(request.into_inner(), &user)
@@ -319,4 +435,625 @@ impl ArkalisCoreService for ArkalisGrpcServerServices {
             .await?;
         Ok(Response::new(response))
     }
+
+    type StreamSearchAnimeStream = ResponseStream<Anime>;
+
+    async fn stream_search_anime(
+        &self,
+        request: Request<SearchAnimeRequest>,
+    ) -> Result<Response<Self::StreamSearchAnimeStream>, Status> {
+        let user = request.get_user(&self.authentication_cache).await.ok();
+        let animes = self
+            .anime_service
+            .search_anime_stream(request.into_inner(), &user)?;
+        Ok(Response::new(to_response_stream(animes)))
+    }
+
+    type StreamSourcesStream = ResponseStream<Sources>;
+
+    async fn stream_sources(
+        &self,
+        request: Request<GetSourcesRequest>,
+    ) -> Result<Response<Self::StreamSourcesStream>, Status> {
+        let sources = self.source_service.get_sources_stream(request.into_inner());
+        Ok(Response::new(to_response_stream(sources)))
+    }
+
+    type StreamEpisodesBySeasonAndSourceStream = ResponseStream<Episode>;
+
+    async fn stream_episodes_by_season_and_source(
+        &self,
+        request: Request<GetEpisodesBySeasonAndSourceRequest>,
+    ) -> Result<Response<Self::StreamEpisodesBySeasonAndSourceStream>, Status> {
+        let episodes = self
+            .episode_service
+            .get_episodes_by_season_and_source_stream(request.into_inner());
+        Ok(Response::new(to_response_stream(episodes)))
+    }
+
+    type StreamAnimeSeasonsStream = ResponseStream<Season>;
+
+    async fn stream_anime_seasons(
+        &self,
+        request: Request<GetAnimeSeasonsRequest>,
+    ) -> Result<Response<Self::StreamAnimeSeasonsStream>, Status> {
+        let seasons = self
+            .season_service
+            .get_by_anime_stream(request.into_inner());
+        Ok(Response::new(to_response_stream(seasons)))
+    }
+
+    async fn delete_anime(
+        &self,
+        request: Request<DeleteAnimeRequest>,
+    ) -> Result<Response<DeleteAnimeResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .anime_service
+            .delete_anime(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn delete_season(
+        &self,
+        request: Request<DeleteSeasonRequest>,
+    ) -> Result<Response<DeleteSeasonResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .season_service
+            .delete_season(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn delete_source(
+        &self,
+        request: Request<DeleteSourceRequest>,
+    ) -> Result<Response<DeleteSourceResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .source_service
+            .delete_source(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn delete_episode(
+        &self,
+        request: Request<DeleteEpisodeRequest>,
+    ) -> Result<Response<DeleteEpisodeResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .episode_service
+            .delete_episode(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn restore_entity(
+        &self,
+        request: Request<RestoreEntityRequest>,
+    ) -> Result<Response<RestoreEntityResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .trash_service
+            .restore(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn purge_entity(
+        &self,
+        request: Request<PurgeEntityRequest>,
+    ) -> Result<Response<PurgeEntityResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .trash_service
+            .purge(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn list_audit_events(
+        &self,
+        request: Request<ListAuditEventsRequest>,
+    ) -> Result<Response<ListAuditEventsResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .audit_service
+            .list_events(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn list_anime_revisions(
+        &self,
+        request: Request<ListAnimeRevisionsRequest>,
+    ) -> Result<Response<ListAnimeRevisionsResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .anime_service
+            .list_revisions(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn get_anime_revision(
+        &self,
+        request: Request<GetAnimeRevisionRequest>,
+    ) -> Result<Response<GetAnimeRevisionResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .anime_service
+            .get_revision(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn revert_anime(
+        &self,
+        request: Request<RevertAnimeRequest>,
+    ) -> Result<Response<RevertAnimeResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .anime_service
+            .revert_anime(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn list_users(
+        &self,
+        request: Request<ListUsersRequest>,
+    ) -> Result<Response<ListUsersResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .list_users(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn set_user_role(
+        &self,
+        request: Request<SetUserRoleRequest>,
+    ) -> Result<Response<SetUserRoleResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .set_user_role(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn set_user_ban(
+        &self,
+        request: Request<SetUserBanRequest>,
+    ) -> Result<Response<SetUserBanResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .set_user_ban(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn rename_user(
+        &self,
+        request: Request<RenameUserRequest>,
+    ) -> Result<Response<RenameUserResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .rename_user(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn list_sessions(
+        &self,
+        request: Request<ListSessionsRequest>,
+    ) -> Result<Response<ListSessionsResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .list_sessions(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn revoke_session(
+        &self,
+        request: Request<RevokeSessionRequest>,
+    ) -> Result<Response<RevokeSessionResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .revoke_session(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn revoke_all_sessions(
+        &self,
+        request: Request<RevokeAllSessionsRequest>,
+    ) -> Result<Response<RevokeAllSessionsResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .revoke_all_sessions(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn refresh_token(
+        &self,
+        request: Request<RefreshTokenRequest>,
+    ) -> Result<Response<RefreshTokenResponse>, Status> {
+        let response = self
+            .user_service
+            .refresh_token(request.into_inner())
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn exchange_legacy_token(
+        &self,
+        request: Request<ExchangeLegacyTokenRequest>,
+    ) -> Result<Response<ExchangeLegacyTokenResponse>, Status> {
+        let response = self
+            .user_service
+            .exchange_legacy_token(request.into_inner())
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn rotate_recovery_key(
+        &self,
+        request: Request<RotateRecoveryKeyRequest>,
+    ) -> Result<Response<RotateRecoveryKeyResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .rotate_recovery_key(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn link_credentials(
+        &self,
+        request: Request<LinkCredentialsRequest>,
+    ) -> Result<Response<LinkCredentialsResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .link_credentials(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn change_password(
+        &self,
+        request: Request<ChangePasswordRequest>,
+    ) -> Result<Response<ChangePasswordResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .change_password(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn login(
+        &self,
+        request: Request<LoginRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        let client = request.get_client_address(&self.user_service.config)?;
+        let response = self
+            .user_service
+            .login(request.into_inner(), &client)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn update_profile(
+        &self,
+        request: Request<UpdateProfileRequest>,
+    ) -> Result<Response<UpdateProfileResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .user_service
+            .update_profile(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn import_watch_status(
+        &self,
+        request: Request<ImportWatchStatusRequest>,
+    ) -> Result<Response<ImportWatchStatusResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .anime_list_service
+            .import_watch_status(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn import_anime(
+        &self,
+        request: Request<ImportAnimeRequest>,
+    ) -> Result<Response<ImportAnimeResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .anime_service
+            .import_anime(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn get_anime_by_external_id(
+        &self,
+        request: Request<GetAnimeByExternalIdRequest>,
+    ) -> Result<Response<GetAnimeByExternalIdResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await.ok();
+        let response = self
+            .anime_service
+            .get_anime_by_external_id(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn report_watch_progress(
+        &self,
+        request: Request<ReportWatchProgressRequest>,
+    ) -> Result<Response<ReportWatchProgressResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .watch_progress_service
+            .report_progress(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn get_continue_watching(
+        &self,
+        request: Request<GetContinueWatchingRequest>,
+    ) -> Result<Response<GetContinueWatchingResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .watch_progress_service
+            .continue_watching(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn mark_season_watched(
+        &self,
+        request: Request<MarkSeasonWatchedRequest>,
+    ) -> Result<Response<MarkSeasonWatchedResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .watch_progress_service
+            .mark_season_watched(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn set_anime_list_entry(
+        &self,
+        request: Request<SetAnimeListEntryRequest>,
+    ) -> Result<Response<SetAnimeListEntryResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .anime_list_service
+            .set_entry(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn remove_anime_list_entry(
+        &self,
+        request: Request<RemoveAnimeListEntryRequest>,
+    ) -> Result<Response<RemoveAnimeListEntryResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .anime_list_service
+            .remove_entry(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn list_anime_list_entries(
+        &self,
+        request: Request<ListAnimeListEntriesRequest>,
+    ) -> Result<Response<ListAnimeListEntriesResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .anime_list_service
+            .list_entries(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn rate_anime(
+        &self,
+        request: Request<RateAnimeRequest>,
+    ) -> Result<Response<RateAnimeResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .rating_service
+            .rate_anime(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn delete_anime_rating(
+        &self,
+        request: Request<DeleteAnimeRatingRequest>,
+    ) -> Result<Response<DeleteAnimeRatingResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .rating_service
+            .delete_rating(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn get_anime_rating(
+        &self,
+        request: Request<GetAnimeRatingRequest>,
+    ) -> Result<Response<GetAnimeRatingResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .rating_service
+            .get_rating(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn create_comment(
+        &self,
+        request: Request<CreateCommentRequest>,
+    ) -> Result<Response<CreateCommentResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .comment_service
+            .create_comment(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn edit_comment(
+        &self,
+        request: Request<EditCommentRequest>,
+    ) -> Result<Response<EditCommentResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .comment_service
+            .edit_comment(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn delete_comment(
+        &self,
+        request: Request<DeleteCommentRequest>,
+    ) -> Result<Response<DeleteCommentResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .comment_service
+            .delete_comment(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn list_comments(
+        &self,
+        request: Request<ListCommentsRequest>,
+    ) -> Result<Response<ListCommentsResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await.ok();
+        let response = self
+            .comment_service
+            .list_comments(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn moderate_comment(
+        &self,
+        request: Request<ModerateCommentRequest>,
+    ) -> Result<Response<ModerateCommentResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .comment_service
+            .moderate_comment(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn follow_anime(
+        &self,
+        request: Request<FollowAnimeRequest>,
+    ) -> Result<Response<FollowAnimeResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .notification_service
+            .follow_anime(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn unfollow_anime(
+        &self,
+        request: Request<UnfollowAnimeRequest>,
+    ) -> Result<Response<UnfollowAnimeResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .notification_service
+            .unfollow_anime(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn list_followed_animes(
+        &self,
+        request: Request<ListFollowedAnimesRequest>,
+    ) -> Result<Response<ListFollowedAnimesResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .notification_service
+            .list_followed(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn list_notifications(
+        &self,
+        request: Request<ListNotificationsRequest>,
+    ) -> Result<Response<ListNotificationsResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .notification_service
+            .list_notifications(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn mark_notifications_read(
+        &self,
+        request: Request<MarkNotificationsReadRequest>,
+    ) -> Result<Response<MarkNotificationsReadResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .notification_service
+            .mark_read(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn clear_notifications(
+        &self,
+        request: Request<ClearNotificationsRequest>,
+    ) -> Result<Response<ClearNotificationsResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .notification_service
+            .clear(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn list_broken_episodes(
+        &self,
+        request: Request<ListBrokenEpisodesRequest>,
+    ) -> Result<Response<ListBrokenEpisodesResponse>, Status> {
+        let user = request.get_user(&self.authentication_cache).await?;
+        let response = self
+            .media_health_service
+            .list_broken_episodes(request.into_inner(), &user)
+            .await?;
+        Ok(Response::new(response))
+    }
 }
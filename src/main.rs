@@ -8,6 +8,7 @@ use crate::models::config::Config;
 
 mod extensions;
 mod grpc_calls;
+mod media_providers;
 mod models;
 mod repositories;
 mod services;
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use reqwest::header::RANGE;
+use reqwest::redirect::Policy;
+use reqwest::{Client, StatusCode, Url};
+
+use crate::media_providers::{is_local_url, MediaProvider};
+use crate::models::error::ApplicationError;
+use crate::models::media_resolution_error::MediaResolutionError;
+use crate::view_models::odysee::Media;
+
+const MAX_URL_LENGTH: usize = 255;
+const MAX_REDIRECTS: usize = 10;
+
+pub struct HttpProvider {
+    pub timeout: Duration,
+}
+
+#[tonic::async_trait]
+impl MediaProvider for HttpProvider {
+    fn parse_reference(&self, reference: &str) -> Result<String, ApplicationError> {
+        let url = Url::parse(reference.trim()).map_err(|_| {
+            ApplicationError::InvalidData(anyhow::Error::msg("media URL is invalid"))
+        })?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "media URL must use http or https",
+            )));
+        }
+
+        let url = url.to_string();
+        if is_local_url(&url) {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "media URL must not point at a local address",
+            )));
+        }
+        if url.len() > MAX_URL_LENGTH {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "media URL is too long",
+            )));
+        }
+
+        Ok(url)
+    }
+
+    async fn resolve(&self, media_id: &str) -> Result<Media, MediaResolutionError> {
+        // Stored URLs may predate the check in `parse_reference`, and redirects are
+        // followed by the server, so both are held to the same rule.
+        if is_local_url(media_id) {
+            return Err(MediaResolutionError::InvalidResponse(
+                "media URL points at a local address".into(),
+            ));
+        }
+
+        let redirect_policy = Policy::custom(|attempt| {
+            if is_local_url(attempt.url().as_str()) {
+                attempt.error("redirect points at a local address")
+            } else if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else {
+                attempt.follow()
+            }
+        });
+        let client = Client::builder()
+            .timeout(self.timeout)
+            .redirect(redirect_policy)
+            .build()?;
+        let mut status = client.head(media_id).send().await?.status();
+        if status == StatusCode::METHOD_NOT_ALLOWED {
+            status = client
+                .get(media_id)
+                .header(RANGE, "bytes=0-0")
+                .send()
+                .await?
+                .status();
+        }
+
+        if matches!(status, StatusCode::NOT_FOUND | StatusCode::GONE) {
+            return Err(MediaResolutionError::NotFound(media_id.to_string()));
+        }
+        if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(MediaResolutionError::Unavailable(status.to_string()));
+        }
+        if !status.is_success() {
+            return Err(MediaResolutionError::InvalidResponse(status.to_string()));
+        }
+
+        // Direct files and HLS playlists are played from the URL itself.
+        Ok(Media {
+            content_url: Some(media_id.to_string()),
+            ..Default::default()
+        })
+    }
+}
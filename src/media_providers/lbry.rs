@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::media_providers::{is_local_url, MediaProvider};
+use crate::models::error::ApplicationError;
+use crate::models::media_resolution_error::MediaResolutionError;
+use crate::view_models::lbry::{Claim, GetResult, JsonRpcResponse, ResolveResult};
+use crate::view_models::odysee::Media;
+
+const LBRY_BASE_URL: &str = "https://open.lbry.com/";
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_DELAY_MILLISECONDS: u64 = 500;
+
+pub struct LbryProvider {
+    pub api_url: String,
+    pub timeout: Duration,
+    /// Public CDN the stream URL is built from, skipping the `get` call entirely.
+    pub streaming_base_url: Option<String>,
+}
+
+impl LbryProvider {
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, MediaResolutionError> {
+        let client = Client::builder().timeout(self.timeout).build()?;
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+
+        let mut attempt = 1;
+        loop {
+            match self.send(&client, &body).await {
+                Err(e) if e.is_retryable() && attempt < MAX_ATTEMPTS => {
+                    tokio::time::sleep(Duration::from_millis(
+                        RETRY_DELAY_MILLISECONDS * attempt as u64,
+                    ))
+                    .await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        client: &Client,
+        body: &Value,
+    ) -> Result<T, MediaResolutionError> {
+        let response = client
+            .post(&self.api_url)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.is_server_error() {
+            return Err(MediaResolutionError::Unavailable(status.to_string()));
+        }
+        if !status.is_success() {
+            return Err(MediaResolutionError::InvalidResponse(status.to_string()));
+        }
+
+        let response = response.text().await?;
+        let response = serde_json::from_str::<JsonRpcResponse<T>>(&response)
+            .map_err(|e| MediaResolutionError::InvalidResponse(e.to_string()))?;
+        if let Some(error) = response.error {
+            return Err(MediaResolutionError::InvalidResponse(error.message));
+        }
+
+        response.result.ok_or(MediaResolutionError::InvalidResponse(
+            "result is missing".into(),
+        ))
+    }
+
+    async fn resolve_claim(&self, uri: &str) -> Result<Claim, MediaResolutionError> {
+        let mut result: ResolveResult = self.call("resolve", json!({ "urls": [uri] })).await?;
+        let claim = result
+            .remove(uri)
+            .ok_or(MediaResolutionError::NotFound(uri.to_string()))?;
+
+        if let Some(error) = claim.error {
+            return Err(match error.name.as_deref() {
+                Some("NOT_FOUND") => MediaResolutionError::NotFound(uri.to_string()),
+                _ => MediaResolutionError::InvalidResponse(error.text.unwrap_or_default()),
+            });
+        }
+
+        Ok(claim)
+    }
+
+    async fn streaming_url(
+        &self,
+        uri: &str,
+        claim: &Claim,
+    ) -> Result<String, MediaResolutionError> {
+        if let Some(base_url) = &self.streaming_base_url {
+            let (Some(name), Some(claim_id)) = (&claim.name, &claim.claim_id) else {
+                return Err(MediaResolutionError::InvalidResponse(
+                    "claim name or id is missing".into(),
+                ));
+            };
+            return Ok(format!(
+                "{}/{}/{}",
+                base_url.trim_end_matches('/'),
+                name,
+                claim_id
+            ));
+        }
+
+        // Without `save_file: false` a full SDK downloads the whole stream before answering.
+        let result: GetResult = self
+            .call("get", json!({ "uri": uri, "save_file": false }))
+            .await?;
+        if let Some(error) = result.error {
+            return Err(MediaResolutionError::Unavailable(error));
+        }
+
+        let streaming_url = result
+            .streaming_url
+            .ok_or(MediaResolutionError::InvalidResponse(
+                "streaming_url is missing".into(),
+            ))?;
+        if is_local_url(&streaming_url) {
+            return Err(MediaResolutionError::InvalidResponse(
+                "streaming_url points at a local SDK, set lbry_streaming_base_url".into(),
+            ));
+        }
+
+        Ok(streaming_url)
+    }
+}
+
+#[tonic::async_trait]
+impl MediaProvider for LbryProvider {
+    fn parse_reference(&self, reference: &str) -> Result<String, ApplicationError> {
+        if !reference.starts_with(LBRY_BASE_URL) {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "Url do lbry incorreta",
+            )));
+        }
+
+        let media_id = reference.replace(LBRY_BASE_URL, "");
+        Ok(media_id)
+    }
+
+    async fn resolve(&self, media_id: &str) -> Result<Media, MediaResolutionError> {
+        let uri = format!("lbry://{}", media_id);
+        let claim = self.resolve_claim(&uri).await?;
+        let content_url = self.streaming_url(&uri, &claim).await?;
+
+        let value = claim.value;
+        let video = value.as_ref().and_then(|value| value.video.as_ref());
+        let release_time = value
+            .as_ref()
+            .and_then(|value| value.release_time.as_ref())
+            .and_then(|release_time| release_time.parse::<i64>().ok())
+            .or(claim.timestamp);
+
+        Ok(Media {
+            name: value.as_ref().and_then(|value| value.title.clone()),
+            description: value.as_ref().and_then(|value| value.description.clone()),
+            thumbnail_url: value
+                .as_ref()
+                .and_then(|value| value.thumbnail.as_ref())
+                .and_then(|thumbnail| thumbnail.url.clone()),
+            upload_date: release_time
+                .and_then(|release_time| DateTime::from_timestamp(release_time, 0))
+                .map(|date| date.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            duration: video
+                .and_then(|video| video.duration)
+                .map(|duration| format!("PT{}S", duration)),
+            width: video.and_then(|video| video.width),
+            height: video.and_then(|video| video.height),
+            url: claim.canonical_url,
+            content_url: Some(content_url),
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,88 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use reqwest::Url;
+
+use crate::models::config::Config;
+use crate::models::error::ApplicationError;
+use crate::models::media_provider_kind::MediaProviderKind;
+use crate::models::media_resolution_error::MediaResolutionError;
+use crate::view_models::odysee::Media;
+
+mod http;
+mod lbry;
+mod object_storage;
+
+#[tonic::async_trait]
+pub trait MediaProvider: Send + Sync {
+    /// Validates a user supplied reference and returns the id stored in `episodes.media_id`.
+    fn parse_reference(&self, reference: &str) -> Result<String, ApplicationError>;
+
+    /// Resolves a stored media id into its metadata, `content_url` being the URL handed to players.
+    async fn resolve(&self, media_id: &str) -> Result<Media, MediaResolutionError>;
+}
+
+const DEFAULT_LBRY_API_URL: &str = "https://api.na-backend.odysee.com/api/v1/proxy";
+const DEFAULT_LBRY_API_TIMEOUT_SECONDS: u64 = 10;
+const DEFAULT_HTTP_TIMEOUT_SECONDS: u64 = 10;
+
+pub fn media_provider(kind: MediaProviderKind, config: &Config) -> Box<dyn MediaProvider> {
+    match kind {
+        MediaProviderKind::Lbry => Box::new(lbry::LbryProvider {
+            api_url: config
+                .lbry_api_url
+                .clone()
+                .unwrap_or(DEFAULT_LBRY_API_URL.into()),
+            timeout: Duration::from_secs(
+                config
+                    .lbry_api_timeout_seconds
+                    .unwrap_or(DEFAULT_LBRY_API_TIMEOUT_SECONDS),
+            ),
+            streaming_base_url: config.lbry_streaming_base_url.clone(),
+        }),
+        MediaProviderKind::Http => Box::new(http::HttpProvider {
+            timeout: Duration::from_secs(DEFAULT_HTTP_TIMEOUT_SECONDS),
+        }),
+        MediaProviderKind::ObjectStorage => Box::new(object_storage::ObjectStorageProvider {
+            base_url: config.object_storage_base_url.clone(),
+        }),
+    }
+}
+
+/// Media URLs are fetched by the server, so they must not reach loopback, private or
+/// link-local addresses.
+fn is_local_url(url: &str) -> bool {
+    let Some(host) = Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_lowercase))
+    else {
+        return false;
+    };
+
+    match host.trim_start_matches('[').trim_end_matches(']').parse() {
+        Ok(ip) => is_local_ip(ip),
+        Err(_) => host == "localhost" || host.ends_with(".localhost"),
+    }
+}
+
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_local_ip(IpAddr::V4(ip)),
+            None => {
+                let segment = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || (segment & 0xfe00) == 0xfc00
+                    || (segment & 0xffc0) == 0xfe80
+            }
+        },
+    }
+}
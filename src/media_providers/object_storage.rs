@@ -0,0 +1,45 @@
+use crate::media_providers::MediaProvider;
+use crate::models::error::ApplicationError;
+use crate::models::media_resolution_error::MediaResolutionError;
+use crate::view_models::odysee::Media;
+
+const MAX_KEY_LENGTH: usize = 255;
+
+pub struct ObjectStorageProvider {
+    pub base_url: Option<String>,
+}
+
+#[tonic::async_trait]
+impl MediaProvider for ObjectStorageProvider {
+    fn parse_reference(&self, reference: &str) -> Result<String, ApplicationError> {
+        let key = reference.trim().trim_start_matches('/');
+        let is_valid = !key.is_empty()
+            && key.len() <= MAX_KEY_LENGTH
+            && key
+                .split('/')
+                .all(|part| !part.is_empty() && part != "." && part != "..")
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '.' | '-' | '_'));
+        if !is_valid {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "object key is invalid",
+            )));
+        }
+
+        Ok(key.to_string())
+    }
+
+    async fn resolve(&self, media_id: &str) -> Result<Media, MediaResolutionError> {
+        let base_url = self
+            .base_url
+            .as_deref()
+            .ok_or(MediaResolutionError::Unavailable(
+                "object storage is not configured".into(),
+            ))?;
+        Ok(Media {
+            content_url: Some(format!("{}/{}", base_url.trim_end_matches('/'), media_id)),
+            ..Default::default()
+        })
+    }
+}
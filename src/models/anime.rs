@@ -1,5 +1,6 @@
 use crate::arkalis_service;
 use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::mysql::MySqlRow;
 use sqlx::{Error, FromRow, Row};
 use validator::{Validate, ValidationError};
@@ -8,19 +9,26 @@ use crate::arkalis_service::{CreateAnimeRequest, EditAnimeRequest};
 use crate::models::anime_in_anime_list::AnimeInAnimeList;
 use crate::models::error::ApplicationError;
 use crate::models::roles::Roles;
+use crate::models::search_text;
 use crate::models::title::Title;
 use crate::models::user::User;
 
 use super::genre::Genre;
 
-#[derive(Validate)]
+#[derive(Validate, Clone, Serialize, Deserialize)]
 pub struct Anime {
+    #[serde(skip)]
     pub id: Option<u32>,
     #[validate(custom(function = "validate_titles"))]
     pub titles: Vec<Title>,
+    #[serde(skip)]
     pub title_search: String,
+    #[serde(skip)]
+    pub main_title: String,
     #[validate(length(min = 1, max = 4000))]
     pub synopsis: String,
+    #[serde(skip)]
+    pub synopsis_search: Option<String>,
     #[validate(length(min = 1, max = 255))]
     pub thumbnail_id: Option<String>,
     #[validate(length(min = 1, max = 255))]
@@ -33,6 +41,13 @@ pub struct Anime {
     pub release_date: DateTime<Utc>,
     #[validate(length(min = 1))]
     pub anime_in_lists: Vec<AnimeInAnimeList>,
+    #[serde(skip)]
+    pub relevance: Option<f64>,
+    #[serde(skip)]
+    pub average_score: f64,
+    #[serde(skip)]
+    pub vote_count: u32,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl Anime {
@@ -43,6 +58,7 @@ impl Anime {
 
         let titles = Title::from_grpc_arr(data.titles)?;
         let title_search = generate_title_search(&titles);
+        let main_title = generate_main_title(&titles);
         let release_date =
             DateTime::from_timestamp(data.release_date, 0).ok_or(ApplicationError::InvalidData(
                 anyhow::Error::msg("release_date is not a valid unixtime"),
@@ -56,6 +72,8 @@ impl Anime {
             id: None,
             titles,
             title_search,
+            main_title,
+            synopsis_search: Some(search_text::normalize(&data.synopsis)),
             synopsis: data.synopsis,
             thumbnail_id: data.thumbnail_id,
             banner_id: data.banner_id,
@@ -66,6 +84,10 @@ impl Anime {
             release_date,
             genre,
             anime_in_lists,
+            relevance: None,
+            average_score: 0.0,
+            vote_count: 0,
+            deleted_at: None,
         };
 
         anime.validate()?;
@@ -108,6 +130,8 @@ impl Anime {
 
         self.titles = Title::from_grpc_arr(update_data.titles)?;
         self.title_search = generate_title_search(&self.titles);
+        self.main_title = generate_main_title(&self.titles);
+        self.synopsis_search = Some(search_text::normalize(&update_data.synopsis));
         self.synopsis = update_data.synopsis;
         self.thumbnail_id = update_data.thumbnail_id;
         self.banner_id = update_data.banner_id;
@@ -125,6 +149,41 @@ impl Anime {
         Ok(self)
     }
 
+    pub fn delete(mut self, user: &User) -> Result<Self, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        self.deleted_at = Some(Utc::now());
+
+        Ok(self)
+    }
+
+    pub fn revert(mut self, revision: Anime, user: &User) -> Result<Self, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        self.titles = revision.titles;
+        self.title_search = generate_title_search(&self.titles);
+        self.main_title = generate_main_title(&self.titles);
+        self.synopsis_search = Some(search_text::normalize(&revision.synopsis));
+        self.synopsis = revision.synopsis;
+        self.thumbnail_id = revision.thumbnail_id;
+        self.banner_id = revision.banner_id;
+        self.genre = revision.genre;
+        self.release_date = revision.release_date;
+        self.anime_in_lists = revision.anime_in_lists;
+        self.is_hidden = revision.is_hidden;
+
+        Ok(self)
+    }
+
+    pub fn reindex_search(&mut self) {
+        self.title_search = generate_title_search(&self.titles);
+        self.synopsis_search = Some(search_text::normalize(&self.synopsis));
+    }
+
     fn titles_from_json(json: &str) -> Result<Vec<Title>, ApplicationError> {
         let titles = serde_json::from_str::<Vec<Title>>(json)
             .map_err(|e| ApplicationError::UnknownError(e.into()))?;
@@ -156,6 +215,12 @@ impl FromRow<'_, MySqlRow> for Anime {
             type_name: "Vec<AnimeInAnimeList>".into(),
         })?;
 
+        let relevance = match row.try_get("relevance") {
+            Ok(relevance) => relevance,
+            Err(Error::ColumnNotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+
         let release_date: NaiveDate = row.try_get("release_date")?;
         let release_date = DateTime::from_naive_utc_and_offset(release_date.into(), Utc);
 
@@ -163,7 +228,9 @@ impl FromRow<'_, MySqlRow> for Anime {
             id: row.try_get("id")?,
             titles,
             title_search: row.try_get("title_search")?,
+            main_title: row.try_get("main_title")?,
             synopsis: row.try_get("synopsis")?,
+            synopsis_search: row.try_get("synopsis_search")?,
             thumbnail_id: row.try_get("thumbnail_id")?,
             banner_id: row.try_get("banner_id")?,
             is_hidden: row.try_get("is_hidden")?,
@@ -173,6 +240,10 @@ impl FromRow<'_, MySqlRow> for Anime {
             genre,
             release_date,
             anime_in_lists,
+            relevance,
+            average_score: row.try_get("average_score")?,
+            vote_count: row.try_get("score_count")?,
+            deleted_at: row.try_get("deleted_at")?,
         };
 
         Ok(anime)
@@ -202,6 +273,9 @@ impl From<Anime> for arkalis_service::Anime {
                 .into_iter()
                 .map(arkalis_service::AnimeInAnimeList::from)
                 .collect::<Vec<_>>(),
+            relevance: value.relevance,
+            average_score: value.average_score,
+            vote_count: value.vote_count,
         }
     }
 }
@@ -209,9 +283,17 @@ impl From<Anime> for arkalis_service::Anime {
 fn generate_title_search(titles: &[Title]) -> String {
     titles
         .iter()
-        .map(|x| x.name.clone())
+        .map(|x| search_text::normalize(&x.name))
         .collect::<Vec<_>>()
-        .join("_")
+        .join(" ")
+}
+
+fn generate_main_title(titles: &[Title]) -> String {
+    titles
+        .iter()
+        .find(|x| x.is_main)
+        .map(|x| x.name.clone())
+        .unwrap_or_default()
 }
 
 fn validate_titles(titles: &[Title]) -> Result<(), ValidationError> {
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::anime::Anime;
+use crate::models::anime_sort_key::AnimeSortKey;
+use crate::models::cursor::Cursor;
+use crate::models::error::ApplicationError;
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AnimeCursorValue {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AnimeCursor {
+    pub sort_key: AnimeSortKey,
+    pub descending: bool,
+    pub value: AnimeCursorValue,
+    pub id: u32,
+}
+
+impl AnimeCursor {
+    pub fn from_anime(
+        anime: &Anime,
+        sort_key: AnimeSortKey,
+        descending: bool,
+    ) -> Result<Self, ApplicationError> {
+        let id = anime
+            .id
+            .ok_or(ApplicationError::UnknownError(anyhow::Error::msg(
+                "entity id is null",
+            )))?;
+
+        let value = match sort_key {
+            AnimeSortKey::Id => AnimeCursorValue::Integer(id as i64),
+            AnimeSortKey::ReleaseDate => AnimeCursorValue::Integer(anime.release_date.timestamp()),
+            AnimeSortKey::CreatedAt => AnimeCursorValue::Integer(anime.created_at.timestamp()),
+            AnimeSortKey::MainTitle => AnimeCursorValue::Text(anime.main_title.clone()),
+            AnimeSortKey::Relevance => AnimeCursorValue::Float(anime.relevance.unwrap_or(0.0)),
+            AnimeSortKey::Score => AnimeCursorValue::Float(anime.average_score),
+        };
+
+        Ok(Self {
+            sort_key,
+            descending,
+            value,
+            id,
+        })
+    }
+
+    pub fn parse(
+        cursor: &str,
+        sort_key: AnimeSortKey,
+        descending: bool,
+    ) -> Result<Self, ApplicationError> {
+        let cursor = Self::decode(cursor)?;
+        if cursor.sort_key != sort_key || cursor.descending != descending {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "cursor does not match the requested sorting",
+            )));
+        }
+
+        Ok(cursor)
+    }
+}
+
+impl Cursor for AnimeCursor {}
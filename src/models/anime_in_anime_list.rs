@@ -5,7 +5,7 @@ use num_traits::{FromPrimitive, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-#[derive(Validate, Serialize, Deserialize)]
+#[derive(Validate, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AnimeInAnimeList {
     anime_list: AnimeList,
     #[validate(length(min = 1, max = 100))]
@@ -13,6 +13,21 @@ pub struct AnimeInAnimeList {
 }
 
 impl AnimeInAnimeList {
+    pub fn new(anime_list: AnimeList, id_in_list: String) -> Self {
+        Self {
+            anime_list,
+            id_in_list,
+        }
+    }
+
+    pub fn anime_list(&self) -> AnimeList {
+        self.anime_list
+    }
+
+    pub fn id_in_list(&self) -> &str {
+        &self.id_in_list
+    }
+
     pub fn from_grpc(value: arkalis_service::AnimeInAnimeList) -> Result<Self, ApplicationError> {
         let anime_list = AnimeList::from_i32(value.anime_list).ok_or(
             ApplicationError::InvalidData(anyhow::Error::msg("anime_list type is invalid")),
@@ -1,7 +1,7 @@
 use num_derive::{FromPrimitive, ToPrimitive};
 use serde::{Deserialize, Serialize};
 
-#[derive(FromPrimitive, ToPrimitive, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Copy, FromPrimitive, ToPrimitive, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum AnimeList {
     MyAnimeList,
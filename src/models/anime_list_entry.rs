@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use num_traits::{FromPrimitive, ToPrimitive};
+use sqlx::mysql::MySqlRow;
+use sqlx::{Error, FromRow, Row};
+use validator::Validate;
+
+use crate::arkalis_service;
+use crate::arkalis_service::SetAnimeListEntryRequest;
+use crate::models::error::ApplicationError;
+use crate::models::user::User;
+use crate::models::watch_status::WatchStatus;
+
+#[derive(Validate)]
+pub struct AnimeListEntry {
+    pub user_id: String,
+    pub anime_id: u32,
+    pub status: WatchStatus,
+    #[validate(range(min = 1, max = 10))]
+    pub score: Option<u8>,
+    pub progress: Option<u32>,
+    #[validate(length(max = 2000))]
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AnimeListEntry {
+    pub fn new(
+        user: &User,
+        anime_id: u32,
+        status: WatchStatus,
+        score: Option<u8>,
+        progress: Option<u32>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            user_id: user.id.clone(),
+            anime_id,
+            status,
+            score,
+            progress,
+            notes: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn from_grpc(
+        data: SetAnimeListEntryRequest,
+        user: &User,
+    ) -> Result<Self, ApplicationError> {
+        let status = WatchStatus::from_i32(data.status).ok_or(ApplicationError::InvalidData(
+            anyhow::Error::msg("status is invalid"),
+        ))?;
+        let score = data
+            .score
+            .map(|score| {
+                u8::try_from(score).map_err(|_| {
+                    ApplicationError::InvalidData(anyhow::Error::msg("score is invalid"))
+                })
+            })
+            .transpose()?;
+
+        let mut entry = Self::new(user, data.anime_id, status, score, data.progress);
+        entry.notes = data
+            .notes
+            .map(|notes| notes.trim().to_string())
+            .filter(|notes| !notes.is_empty());
+        entry.validate()?;
+
+        Ok(entry)
+    }
+}
+
+impl FromRow<'_, MySqlRow> for AnimeListEntry {
+    fn from_row(row: &'_ MySqlRow) -> Result<Self, Error> {
+        let status = WatchStatus::from_u8(row.try_get("status")?).ok_or(Error::TypeNotFound {
+            type_name: "WatchStatus".into(),
+        })?;
+
+        let entry = Self {
+            user_id: row.try_get("user_id")?,
+            anime_id: row.try_get("anime_id")?,
+            status,
+            score: row.try_get("score")?,
+            progress: row.try_get("progress")?,
+            notes: row.try_get("notes")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        };
+
+        Ok(entry)
+    }
+}
+
+impl From<AnimeListEntry> for arkalis_service::AnimeListEntry {
+    fn from(value: AnimeListEntry) -> Self {
+        Self {
+            anime_id: value.anime_id,
+            status: value.status.to_i32().unwrap_or(0),
+            score: value.score.map(u32::from),
+            progress: value.progress,
+            notes: value.notes,
+            created_at: value.created_at.timestamp(),
+            updated_at: value.updated_at.timestamp(),
+        }
+    }
+}
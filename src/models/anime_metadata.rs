@@ -0,0 +1,258 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use num_traits::ToPrimitive;
+use regex::Regex;
+use serde_json::json;
+
+use crate::arkalis_service;
+use crate::arkalis_service::{CreateAnimeRequest, EditAnimeRequest};
+use crate::models::anime_list::AnimeList;
+use crate::models::config::Config;
+use crate::models::error::ApplicationError;
+use crate::models::genre::Genre;
+use crate::models::metadata_provider_error::MetadataProviderError;
+use crate::models::title_type::TitleType;
+use crate::view_models::anilist_media::{AniListMedia, AniListMediaResponse};
+
+const DEFAULT_ANILIST_API_URL: &str = "https://graphql.anilist.co";
+const REQUEST_TIMEOUT_SECONDS: u64 = 15;
+const MAX_SYNOPSIS_LENGTH: usize = 4000;
+const MEDIA_QUERY: &str = "query ($id: Int, $idMal: Int) { Media(id: $id, idMal: $idMal, type: ANIME) { id idMal title { romaji english native } description(asHtml: false) genres isAdult startDate { year month day } } }";
+
+pub struct AnimeMetadata {
+    pub titles: Vec<arkalis_service::Title>,
+    pub synopsis: String,
+    pub genre: Genre,
+    pub is_nsfw: bool,
+    pub release_date: i64,
+    pub anime_in_lists: Vec<arkalis_service::AnimeInAnimeList>,
+}
+
+impl AnimeMetadata {
+    pub async fn fetch(
+        config: &Config,
+        anime_list: AnimeList,
+        id_in_list: &str,
+    ) -> Result<Self, ApplicationError> {
+        let id: u64 = id_in_list.parse().map_err(|_| {
+            ApplicationError::InvalidData(anyhow::Error::msg("id_in_list is not a valid number"))
+        })?;
+        let variables = match anime_list {
+            AnimeList::AniList => json!({ "id": id }),
+            AnimeList::MyAnimeList => json!({ "idMal": id }),
+        };
+        let body = json!({ "query": MEDIA_QUERY, "variables": variables });
+
+        let url = config
+            .anilist_api_url
+            .as_deref()
+            .unwrap_or(DEFAULT_ANILIST_API_URL);
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+            .build()
+            .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        let response = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(MetadataProviderError::from)?;
+        let status = response.status();
+        let response = response.text().await.map_err(MetadataProviderError::from)?;
+        // AniList answers misses with a 404 and a regular error body, so only an
+        // unreadable body on a failed status means the provider itself is down.
+        let response = serde_json::from_str::<AniListMediaResponse>(&response).map_err(|e| {
+            if status.is_success() {
+                MetadataProviderError::InvalidResponse(e.to_string())
+            } else {
+                MetadataProviderError::Unavailable(status.to_string())
+            }
+        })?;
+
+        if let Some(error) = response.errors.and_then(|errors| errors.into_iter().next()) {
+            if response
+                .data
+                .as_ref()
+                .and_then(|data| data.media.as_ref())
+                .is_none()
+            {
+                return Err(match error.message.as_str() {
+                    "Not Found." => ApplicationError::NotFound,
+                    message if !status.is_success() => {
+                        MetadataProviderError::Unavailable(message.to_string()).into()
+                    }
+                    message => MetadataProviderError::InvalidResponse(message.to_string()).into(),
+                });
+            }
+        }
+
+        let media = response
+            .data
+            .and_then(|data| data.media)
+            .ok_or(ApplicationError::NotFound)?;
+
+        Self::from_media(media)
+    }
+
+    pub fn to_create_request(&self) -> CreateAnimeRequest {
+        CreateAnimeRequest {
+            titles: self.titles.clone(),
+            synopsis: self.synopsis.clone(),
+            thumbnail_id: None,
+            banner_id: None,
+            is_hidden: true,
+            is_nsfw: self.is_nsfw,
+            genre: self.genre.bits(),
+            release_date: self.release_date,
+            anime_in_lists: self.anime_in_lists.clone(),
+        }
+    }
+
+    pub fn to_edit_request(&self, current: arkalis_service::Anime) -> EditAnimeRequest {
+        let portuguese = TitleType::Portuguese.to_i32().unwrap_or(0);
+        let kept_titles = current
+            .titles
+            .into_iter()
+            .filter(|title| title.title_type == portuguese)
+            .collect::<Vec<_>>();
+        let has_kept_main = kept_titles.iter().any(|title| title.is_main);
+
+        let mut titles = self
+            .titles
+            .iter()
+            .cloned()
+            .map(|mut title| {
+                title.is_main = title.is_main && !has_kept_main;
+                title
+            })
+            .collect::<Vec<_>>();
+        titles.extend(kept_titles);
+
+        let mut anime_in_lists = current.anime_in_lists;
+        for entry in &self.anime_in_lists {
+            if !anime_in_lists.contains(entry) {
+                anime_in_lists.push(entry.clone());
+            }
+        }
+
+        EditAnimeRequest {
+            id: current.id,
+            titles,
+            synopsis: self.synopsis.clone(),
+            thumbnail_id: current.thumbnail_id,
+            banner_id: current.banner_id,
+            genre: self.genre.bits(),
+            release_date: self.release_date,
+            anime_in_lists,
+            is_hidden: current.is_hidden,
+        }
+    }
+
+    fn from_media(media: AniListMedia) -> Result<Self, ApplicationError> {
+        let mut titles = Vec::new();
+        let main_type = if media.title.romaji.is_some() {
+            TitleType::Romaji
+        } else if media.title.english.is_some() {
+            TitleType::English
+        } else {
+            TitleType::Native
+        };
+        for (name, title_type) in [
+            (media.title.romaji, TitleType::Romaji),
+            (media.title.english, TitleType::English),
+            (media.title.native, TitleType::Native),
+        ] {
+            if let Some(name) = name.filter(|name| !name.trim().is_empty()) {
+                titles.push(arkalis_service::Title {
+                    name,
+                    is_main: title_type.to_i32() == main_type.to_i32(),
+                    title_type: title_type.to_i32().unwrap_or(0),
+                });
+            }
+        }
+
+        let genre = media
+            .genres
+            .iter()
+            .filter_map(|genre| map_genre(genre))
+            .fold(Genre::Unknown, |acc, genre| acc | genre);
+
+        let start_date =
+            media
+                .start_date
+                .ok_or(ApplicationError::InvalidData(anyhow::Error::msg(
+                    "anime has no start date",
+                )))?;
+        let release_date = NaiveDate::from_ymd_opt(
+            start_date
+                .year
+                .ok_or(ApplicationError::InvalidData(anyhow::Error::msg(
+                    "anime has no start year",
+                )))?,
+            start_date.month.unwrap_or(1),
+            start_date.day.unwrap_or(1),
+        )
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .ok_or(ApplicationError::InvalidData(anyhow::Error::msg(
+            "anime start date is invalid",
+        )))?
+        .and_utc()
+        .timestamp();
+
+        let mut anime_in_lists = vec![arkalis_service::AnimeInAnimeList {
+            anime_list: AnimeList::AniList.to_i32().unwrap_or(0),
+            id_in_list: media.id.to_string(),
+        }];
+        if let Some(id_mal) = media.id_mal {
+            anime_in_lists.push(arkalis_service::AnimeInAnimeList {
+                anime_list: AnimeList::MyAnimeList.to_i32().unwrap_or(0),
+                id_in_list: id_mal.to_string(),
+            });
+        }
+
+        Ok(Self {
+            titles,
+            synopsis: clean_synopsis(media.description.as_deref().unwrap_or_default()),
+            genre,
+            is_nsfw: media.is_adult.unwrap_or(false),
+            release_date,
+            anime_in_lists,
+        })
+    }
+}
+
+fn map_genre(genre: &str) -> Option<Genre> {
+    let genre = match genre {
+        "Action" => Genre::Action,
+        "Adventure" => Genre::Adventure,
+        "Comedy" => Genre::Comedy,
+        "Drama" => Genre::Drama,
+        "Ecchi" => Genre::Ecchi,
+        "Fantasy" => Genre::Fantasy,
+        "Hentai" => Genre::Hentai,
+        "Horror" => Genre::Horror,
+        "Mystery" => Genre::Mystery,
+        "Romance" => Genre::Romance,
+        "Sci-Fi" => Genre::SciFi,
+        "Slice of Life" => Genre::SliceOfLife,
+        "Sports" => Genre::Sports,
+        "Supernatural" => Genre::Supernatural,
+        "Thriller" => Genre::Suspense,
+        _ => return None,
+    };
+
+    Some(genre)
+}
+
+fn clean_synopsis(description: &str) -> String {
+    static HTML_TAG_REGEX: OnceLock<Regex> = OnceLock::new();
+
+    let regex =
+        HTML_TAG_REGEX.get_or_init(|| Regex::new(r"<[^>]*>").expect("html tag pattern is valid"));
+    let synopsis = regex.replace_all(description, "");
+    synopsis.trim().chars().take(MAX_SYNOPSIS_LENGTH).collect()
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::error::ApplicationError;
+use crate::models::user::User;
+
+const MIN_SCORE: u32 = 1;
+const MAX_SCORE: u32 = 10;
+
+pub struct AnimeRating {
+    pub user_id: String,
+    pub anime_id: u32,
+    pub score: u8,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AnimeRating {
+    pub fn new(user: &User, anime_id: u32, score: u32) -> Result<Self, ApplicationError> {
+        if !(MIN_SCORE..=MAX_SCORE).contains(&score) {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "score must be between 1 and 10",
+            )));
+        }
+
+        Ok(Self {
+            user_id: user.id.clone(),
+            anime_id,
+            score: score as u8,
+            updated_at: Utc::now(),
+        })
+    }
+}
@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::mysql::MySqlRow;
+use sqlx::types::Json;
+use sqlx::{Error, FromRow, Row};
+
+use crate::arkalis_service;
+use crate::models::anime::Anime;
+use crate::models::error::ApplicationError;
+use crate::models::user::User;
+
+pub struct AnimeRevision {
+    pub id: Option<u64>,
+    pub anime_id: u32,
+    pub revision: u32,
+    pub snapshot: Value,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub reverted_from: Option<u32>,
+}
+
+impl AnimeRevision {
+    pub fn new(anime_id: u32, snapshot: Value, user: &User, reverted_from: Option<u32>) -> Self {
+        Self {
+            id: None,
+            anime_id,
+            revision: 0,
+            snapshot,
+            created_by: user.id.clone(),
+            created_at: Utc::now(),
+            reverted_from,
+        }
+    }
+
+    pub fn baseline(anime: &Anime) -> Result<Self, ApplicationError> {
+        let anime_id = anime
+            .id
+            .ok_or(ApplicationError::UnknownError(anyhow::Error::msg(
+                "entity id is null",
+            )))?;
+        let snapshot =
+            serde_json::to_value(anime).map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+        Ok(Self {
+            id: None,
+            anime_id,
+            revision: 0,
+            snapshot,
+            created_by: anime.created_by.clone(),
+            created_at: anime.created_at,
+            reverted_from: None,
+        })
+    }
+
+    pub fn to_anime(&self) -> Result<Anime, ApplicationError> {
+        let mut anime = serde_json::from_value::<Anime>(self.snapshot.clone())
+            .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        anime.id = Some(self.anime_id);
+        Ok(anime)
+    }
+}
+
+impl FromRow<'_, MySqlRow> for AnimeRevision {
+    fn from_row(row: &'_ MySqlRow) -> Result<Self, Error> {
+        let Json(snapshot) = row.try_get("snapshot")?;
+
+        let revision = Self {
+            id: row.try_get("id")?,
+            anime_id: row.try_get("anime_id")?,
+            revision: row.try_get("revision")?,
+            snapshot,
+            created_by: row.try_get("created_by")?,
+            created_at: row.try_get("created_at")?,
+            reverted_from: row.try_get("reverted_from")?,
+        };
+
+        Ok(revision)
+    }
+}
+
+impl TryFrom<AnimeRevision> for arkalis_service::AnimeRevision {
+    type Error = ApplicationError;
+
+    fn try_from(value: AnimeRevision) -> Result<Self, Self::Error> {
+        let anime = value.to_anime()?;
+
+        Ok(Self {
+            revision: value.revision,
+            anime: Some(anime.into()),
+            created_by: value.created_by,
+            created_at: value.created_at.timestamp(),
+            reverted_from: value.reverted_from,
+        })
+    }
+}
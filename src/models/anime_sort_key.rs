@@ -0,0 +1,13 @@
+use num_derive::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Clone, Copy, FromPrimitive, ToPrimitive, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum AnimeSortKey {
+    Id,
+    ReleaseDate,
+    CreatedAt,
+    MainTitle,
+    Relevance,
+    Score,
+}
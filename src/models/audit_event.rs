@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use num_traits::{FromPrimitive, ToPrimitive};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::mysql::MySqlRow;
+use sqlx::types::Json;
+use sqlx::{Error, FromRow, Row};
+
+use crate::arkalis_service;
+use crate::models::entity_type::EntityType;
+use crate::models::error::ApplicationError;
+use crate::models::user::User;
+
+pub struct AuditEvent {
+    pub id: Option<u64>,
+    pub user_id: String,
+    pub rpc_name: String,
+    pub entity_type: EntityType,
+    pub entity_id: String,
+    pub diff: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditEvent {
+    pub fn new(
+        user: &User,
+        rpc_name: &str,
+        entity_type: EntityType,
+        entity_id: impl ToString,
+        before: Value,
+        after: Value,
+    ) -> Self {
+        Self {
+            id: None,
+            user_id: user.id.clone(),
+            rpc_name: rpc_name.to_string(),
+            entity_type,
+            entity_id: entity_id.to_string(),
+            diff: diff(before, after),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn snapshot<T: Serialize>(entity: &T) -> Result<Value, ApplicationError> {
+        serde_json::to_value(entity).map_err(|e| ApplicationError::UnknownError(e.into()))
+    }
+}
+
+impl FromRow<'_, MySqlRow> for AuditEvent {
+    fn from_row(row: &'_ MySqlRow) -> Result<Self, Error> {
+        let entity_type =
+            EntityType::from_u8(row.try_get("entity_type")?).ok_or(Error::TypeNotFound {
+                type_name: "EntityType".into(),
+            })?;
+        let Json(diff) = row.try_get("diff")?;
+
+        let event = Self {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            rpc_name: row.try_get("rpc_name")?,
+            entity_type,
+            entity_id: row.try_get("entity_id")?,
+            diff,
+            created_at: row.try_get("created_at")?,
+        };
+
+        Ok(event)
+    }
+}
+
+impl From<AuditEvent> for arkalis_service::AuditEvent {
+    fn from(value: AuditEvent) -> Self {
+        Self {
+            id: value.id.unwrap_or(0),
+            user_id: value.user_id,
+            rpc_name: value.rpc_name,
+            entity_type: value.entity_type.to_i32().unwrap_or(0),
+            entity_id: value.entity_id,
+            diff: value.diff.to_string(),
+            created_at: value.created_at.timestamp(),
+        }
+    }
+}
+
+pub fn diff(before: Value, after: Value) -> Value {
+    match (before, after) {
+        (Value::Object(mut before), Value::Object(after)) => {
+            let mut changes = Map::new();
+            for (key, after_value) in after {
+                let before_value = before.remove(&key).unwrap_or(Value::Null);
+                if before_value != after_value {
+                    changes.insert(key, field_change(before_value, after_value));
+                }
+            }
+
+            for (key, before_value) in before {
+                changes.insert(key, field_change(before_value, Value::Null));
+            }
+
+            Value::Object(changes)
+        }
+        (before, after) if before == after => Value::Object(Map::new()),
+        (before, after) => field_change(before, after),
+    }
+}
+
+fn field_change(before: Value, after: Value) -> Value {
+    let mut change = Map::new();
+    change.insert("before".into(), before);
+    change.insert("after".into(), after);
+    Value::Object(change)
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::cursor::Cursor;
+
+#[derive(Serialize, Deserialize)]
+pub struct AuditEventCursor {
+    pub id: u64,
+}
+
+impl Cursor for AuditEventCursor {}
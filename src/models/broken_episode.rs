@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use num_traits::{FromPrimitive, ToPrimitive};
+use sqlx::mysql::MySqlRow;
+use sqlx::{Error, FromRow, Row};
+
+use crate::arkalis_service;
+use crate::models::media_provider_kind::MediaProviderKind;
+
+pub struct BrokenEpisode {
+    pub episode_id: String,
+    pub season_id: u32,
+    pub source_id: u32,
+    pub sequence: u16,
+    pub media_provider: MediaProviderKind,
+    pub media_id: String,
+    pub is_hidden: bool,
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+    pub auto_hidden: bool,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl FromRow<'_, MySqlRow> for BrokenEpisode {
+    fn from_row(row: &'_ MySqlRow) -> Result<Self, Error> {
+        let media_provider = MediaProviderKind::from_u8(row.try_get("media_provider")?).ok_or(
+            Error::TypeNotFound {
+                type_name: "MediaProviderKind".into(),
+            },
+        )?;
+
+        let episode = Self {
+            episode_id: row.try_get("episode_id")?,
+            season_id: row.try_get("season_id")?,
+            source_id: row.try_get("source_id")?,
+            sequence: row.try_get("sequence")?,
+            media_provider,
+            media_id: row.try_get("media_id")?,
+            is_hidden: row.try_get("is_hidden")?,
+            last_error: row.try_get("last_error")?,
+            consecutive_failures: row.try_get("consecutive_failures")?,
+            auto_hidden: row.try_get("auto_hidden")?,
+            checked_at: row.try_get("checked_at")?,
+        };
+
+        Ok(episode)
+    }
+}
+
+impl From<BrokenEpisode> for arkalis_service::BrokenEpisode {
+    fn from(value: BrokenEpisode) -> Self {
+        Self {
+            episode_id: value.episode_id,
+            season_id: value.season_id,
+            source_id: value.source_id,
+            sequence: value.sequence as u32,
+            media_provider: value.media_provider.to_i32().unwrap_or(0),
+            media_id: value.media_id,
+            is_hidden: value.is_hidden,
+            last_error: value.last_error,
+            consecutive_failures: value.consecutive_failures,
+            auto_hidden: value.auto_hidden,
+            checked_at: value.checked_at.timestamp(),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::cursor::Cursor;
+
+#[derive(Serialize, Deserialize)]
+pub struct BrokenEpisodeCursor {
+    pub consecutive_failures: u32,
+    pub episode_id: String,
+}
+
+impl Cursor for BrokenEpisodeCursor {}
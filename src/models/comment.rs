@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::mysql::MySqlRow;
+use sqlx::{Error, FromRow, Row};
+use validator::Validate;
+
+use crate::arkalis_service;
+use crate::arkalis_service::{CreateCommentRequest, EditCommentRequest};
+use crate::models::comment_moderation_action::CommentModerationAction;
+use crate::models::error::ApplicationError;
+use crate::models::user::User;
+
+#[derive(Validate, Serialize)]
+pub struct Comment {
+    #[serde(skip)]
+    pub id: Option<u64>,
+    pub episode_id: Option<String>,
+    pub anime_id: Option<u32>,
+    pub parent_id: Option<u64>,
+    pub user_id: String,
+    #[serde(skip)]
+    pub display_name: Option<String>,
+    #[validate(length(min = 1, max = 4000))]
+    pub content: String,
+    pub is_spoiler: bool,
+    pub is_hidden: bool,
+    pub is_locked: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub reply_count: u32,
+}
+
+impl Comment {
+    pub fn new(
+        data: CreateCommentRequest,
+        user: &User,
+        thread: Option<&Comment>,
+    ) -> Result<Self, ApplicationError> {
+        let (episode_id, anime_id, parent_id) = match thread {
+            Some(thread) => {
+                if thread.is_locked {
+                    return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                        "thread is locked",
+                    )));
+                }
+                if thread.deleted_at.is_some() || thread.is_hidden {
+                    return Err(ApplicationError::NotFound);
+                }
+                (thread.episode_id.clone(), thread.anime_id, thread.id)
+            }
+            None => match (data.episode_id, data.anime_id) {
+                (Some(episode_id), None) => (Some(episode_id), None, None),
+                (None, Some(anime_id)) => (None, Some(anime_id), None),
+                _ => {
+                    return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                        "exactly one of episode_id or anime_id is required",
+                    )))
+                }
+            },
+        };
+
+        let comment = Self {
+            id: None,
+            episode_id,
+            anime_id,
+            parent_id,
+            user_id: user.id.clone(),
+            display_name: Some(user.display_name.clone()),
+            content: data.content.trim().to_string(),
+            is_spoiler: data.is_spoiler,
+            is_hidden: false,
+            is_locked: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            reply_count: 0,
+        };
+
+        comment.validate()?;
+
+        Ok(comment)
+    }
+
+    pub fn edit(mut self, data: EditCommentRequest, user: &User) -> Result<Self, ApplicationError> {
+        if self.user_id != user.id {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        if self.deleted_at.is_some() {
+            return Err(ApplicationError::NotFound);
+        }
+
+        self.content = data.content.trim().to_string();
+        self.is_spoiler = data.is_spoiler;
+        self.updated_at = Some(Utc::now());
+        self.validate()?;
+
+        Ok(self)
+    }
+
+    pub fn delete(mut self, user: &User) -> Result<Self, ApplicationError> {
+        if self.user_id != user.id && !user.has_uploader_or_adm_role() {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        if self.deleted_at.is_some() {
+            return Err(ApplicationError::NotFound);
+        }
+
+        self.deleted_at = Some(Utc::now());
+
+        Ok(self)
+    }
+
+    pub fn moderate(
+        mut self,
+        action: CommentModerationAction,
+        user: &User,
+    ) -> Result<Self, ApplicationError> {
+        if !user.has_uploader_or_adm_role() {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        match action {
+            CommentModerationAction::Hide => self.is_hidden = true,
+            CommentModerationAction::Unhide => self.is_hidden = false,
+            CommentModerationAction::Delete => return self.delete(user),
+            CommentModerationAction::Lock | CommentModerationAction::Unlock => {
+                if self.parent_id.is_some() {
+                    return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                        "only top-level comments can be locked",
+                    )));
+                }
+                self.is_locked = action == CommentModerationAction::Lock;
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+impl FromRow<'_, MySqlRow> for Comment {
+    fn from_row(row: &'_ MySqlRow) -> Result<Self, Error> {
+        let display_name = match row.try_get("display_name") {
+            Ok(display_name) => display_name,
+            Err(Error::ColumnNotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+
+        let reply_count: i64 = match row.try_get("reply_count") {
+            Ok(reply_count) => reply_count,
+            Err(Error::ColumnNotFound(_)) => 0,
+            Err(e) => return Err(e),
+        };
+
+        let comment = Self {
+            id: row.try_get("id")?,
+            episode_id: row.try_get("episode_id")?,
+            anime_id: row.try_get("anime_id")?,
+            parent_id: row.try_get("parent_id")?,
+            user_id: row.try_get("user_id")?,
+            display_name,
+            content: row.try_get("content")?,
+            is_spoiler: row.try_get("is_spoiler")?,
+            is_hidden: row.try_get("is_hidden")?,
+            is_locked: row.try_get("is_locked")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            deleted_at: row.try_get("deleted_at")?,
+            reply_count: reply_count as u32,
+        };
+
+        Ok(comment)
+    }
+}
+
+impl From<Comment> for arkalis_service::Comment {
+    fn from(value: Comment) -> Self {
+        let is_deleted = value.deleted_at.is_some();
+        Self {
+            id: value.id.unwrap_or(0),
+            episode_id: value.episode_id,
+            anime_id: value.anime_id,
+            parent_id: value.parent_id,
+            user_id: if is_deleted {
+                String::new()
+            } else {
+                value.user_id
+            },
+            display_name: if is_deleted {
+                String::new()
+            } else {
+                value.display_name.unwrap_or_default()
+            },
+            content: if is_deleted {
+                String::new()
+            } else {
+                value.content
+            },
+            is_spoiler: value.is_spoiler,
+            is_hidden: value.is_hidden,
+            is_locked: value.is_locked,
+            is_deleted,
+            reply_count: value.reply_count,
+            created_at: value.created_at.timestamp(),
+            edited_at: value.updated_at.map(|updated_at| updated_at.timestamp()),
+        }
+    }
+}
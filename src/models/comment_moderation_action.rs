@@ -0,0 +1,11 @@
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(PartialEq, Eq, Clone, Copy, FromPrimitive, ToPrimitive)]
+#[repr(u8)]
+pub enum CommentModerationAction {
+    Hide,
+    Unhide,
+    Delete,
+    Lock,
+    Unlock,
+}
@@ -13,6 +13,18 @@ pub struct Config {
     pub database_url: String,
     pub admin_master_key: String,
     pub bind_url: Option<String>,
+    pub client_address_header: Option<String>,
+    pub token_ttl_seconds: Option<i64>,
+    pub refresh_token_ttl_seconds: Option<i64>,
+    pub auth_cache_ttl_seconds: Option<u64>,
+    pub anilist_api_url: Option<String>,
+    pub object_storage_base_url: Option<String>,
+    pub lbry_api_url: Option<String>,
+    pub lbry_api_timeout_seconds: Option<u64>,
+    pub lbry_streaming_base_url: Option<String>,
+    pub media_health_check_interval_seconds: Option<u64>,
+    pub media_health_check_batch_size: Option<u32>,
+    pub media_health_failure_threshold: Option<u32>,
 }
 
 impl Config {
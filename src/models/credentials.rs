@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use validator::Validate;
+
+use crate::models::error::ApplicationError;
+use crate::models::secret_hash;
+use crate::models::user::User;
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+const MAX_PASSWORD_LENGTH: usize = 128;
+
+#[derive(Validate, FromRow)]
+pub struct Credentials {
+    pub user_id: String,
+    #[validate(email, length(max = 255))]
+    pub email: String,
+    pub password_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Credentials {
+    pub async fn new(user: &User, email: &str, password: &str) -> Result<Self, ApplicationError> {
+        let now = Utc::now();
+        let mut credentials = Self {
+            user_id: user.id.clone(),
+            email: normalize_email(email),
+            password_hash: String::new(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        credentials.validate()?;
+        credentials.password_hash = hash_password(password).await?;
+
+        Ok(credentials)
+    }
+
+    pub async fn verify_password(&self, password: &str) -> bool {
+        secret_hash::verify(password, &self.password_hash).await
+    }
+
+    pub async fn change_password(
+        mut self,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<Self, ApplicationError> {
+        if !self.verify_password(current_password).await {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        self.password_hash = hash_password(new_password).await?;
+        self.updated_at = Utc::now();
+
+        Ok(self)
+    }
+}
+
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+async fn hash_password(password: &str) -> Result<String, ApplicationError> {
+    let length = password.chars().count();
+    if !(MIN_PASSWORD_LENGTH..=MAX_PASSWORD_LENGTH).contains(&length) {
+        return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+            "password must have between 8 and 128 characters",
+        )));
+    }
+
+    secret_hash::hash(password).await
+}
@@ -0,0 +1,22 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::models::error::ApplicationError;
+
+pub trait Cursor: Serialize + DeserializeOwned {
+    fn encode(&self) -> Result<String, ApplicationError> {
+        let json =
+            serde_json::to_vec(self).map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        Ok(URL_SAFE_NO_PAD.encode(json))
+    }
+
+    fn decode(cursor: &str) -> Result<Self, ApplicationError> {
+        let json = URL_SAFE_NO_PAD
+            .decode(cursor)
+            .map_err(|_| ApplicationError::InvalidData(anyhow::Error::msg("cursor is invalid")))?;
+        serde_json::from_slice(&json)
+            .map_err(|_| ApplicationError::InvalidData(anyhow::Error::msg("cursor is invalid")))
+    }
+}
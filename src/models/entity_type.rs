@@ -0,0 +1,12 @@
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(PartialEq, Eq, Clone, Copy, FromPrimitive, ToPrimitive)]
+#[repr(u8)]
+pub enum EntityType {
+    Anime,
+    Season,
+    Source,
+    Episode,
+    User,
+    Comment,
+}
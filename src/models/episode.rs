@@ -1,15 +1,26 @@
 use anyhow::anyhow;
+use chrono::{DateTime, Utc};
 use khash::Digest;
+use num_traits::{FromPrimitive, ToPrimitive};
+use serde::Serialize;
 use sqlx::FromRow;
 use validator::Validate;
 
+use crate::arkalis_service;
 use crate::arkalis_service::{CreateEpisodeRequest, UpdateEpisodeRequest};
+use crate::media_providers;
+use crate::models::config::Config;
 use crate::models::error::ApplicationError;
+use crate::models::media_provider_kind::MediaProviderKind;
+use crate::models::media_resolution_error::MediaResolutionError;
 use crate::models::user::User;
-use crate::{arkalis_service, view_models};
+use crate::view_models::odysee::Media;
 
-#[derive(Validate, FromRow)]
+const MAX_THUMBNAIL_URL_LENGTH: usize = 500;
+
+#[derive(Validate, FromRow, Serialize)]
 pub struct Episode {
+    #[serde(skip)]
     pub id: String,
     pub name: String,
     #[validate(length(min = 1, max = 255))]
@@ -17,12 +28,21 @@ pub struct Episode {
     pub season_id: u32,
     pub source_id: u32,
     #[validate(length(min = 1, max = 255))]
-    pub lbry_media_id: Option<String>,
+    pub media_id: Option<String>,
+    #[sqlx(try_from = "u8")]
+    pub media_provider: MediaProviderKind,
     #[validate(length(min = 1, max = 255))]
     pub file_name: Option<String>,
+    pub duration_seconds: Option<u32>,
+    pub width: Option<u16>,
+    pub height: Option<u16>,
+    #[validate(length(min = 1, max = 500))]
+    pub thumbnail_url: Option<String>,
+    pub upload_date: Option<DateTime<Utc>>,
     pub is_nsfw: bool,
     pub sequence: u16,
     pub is_hidden: bool,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl Episode {
@@ -43,11 +63,18 @@ impl Episode {
             cover_id: episode_request.cover_id,
             season_id: episode_request.season_id,
             source_id: episode_request.source_id,
-            lbry_media_id: None,
+            media_id: None,
+            media_provider: MediaProviderKind::Lbry,
             file_name: None,
+            duration_seconds: None,
+            width: None,
+            height: None,
+            thumbnail_url: None,
+            upload_date: None,
             is_nsfw: episode_request.is_nsfw,
             sequence: episode_request.sequence as u16,
             is_hidden: episode_request.is_hidden,
+            deleted_at: None,
         };
 
         Ok(episode)
@@ -57,6 +84,7 @@ impl Episode {
         mut self,
         new_data: UpdateEpisodeRequest,
         user: &User,
+        config: &Config,
     ) -> Result<Self, ApplicationError> {
         if !user.has_uploader_or_adm_role() {
             return Err(ApplicationError::Unauthorized);
@@ -68,18 +96,34 @@ impl Episode {
             )));
         }
 
-        if self.lbry_media_id.is_none() && new_data.lbry_url.is_none() {
+        let media = match (new_data.media, new_data.lbry_url) {
+            (Some(media), _) => {
+                let kind = MediaProviderKind::from_i32(media.provider).ok_or(
+                    ApplicationError::InvalidData(anyhow!("media provider is invalid")),
+                )?;
+                Some((kind, media.reference))
+            }
+            (None, Some(lbry_url)) => Some((MediaProviderKind::Lbry, lbry_url)),
+            (None, None) => None,
+        };
+
+        if self.media_id.is_none() && media.is_none() {
             return Err(ApplicationError::InvalidData(anyhow!(
-                "LBRY URL is required"
+                "media reference is required"
             )));
         }
 
-        if let Some(media_url) = &new_data.lbry_url {
-            let media_id = Self::get_lbry_media_id(media_url)?;
-            let media_id_some = Some(media_id.clone());
-            if media_id_some != self.lbry_media_id {
-                self.lbry_media_id = media_id_some;
-                self.file_name = Some(Self::get_file_name(&media_id).await?)
+        if let Some((kind, reference)) = media {
+            let provider = media_providers::media_provider(kind, config);
+            let media_id = provider.parse_reference(&reference)?;
+            if Some(&media_id) != self.media_id.as_ref() || kind != self.media_provider {
+                let media = provider.resolve(&media_id).await?;
+                self.set_media_metadata(&media);
+                self.file_name = Some(media.content_url.ok_or(
+                    MediaResolutionError::InvalidResponse("content_url is missing".into()),
+                )?);
+                self.media_id = Some(media_id);
+                self.media_provider = kind;
             }
         }
 
@@ -90,23 +134,66 @@ impl Episode {
         Ok(self)
     }
 
+    /// Provider metadata is best effort, so a thumbnail url that wouldn't fit the column is
+    /// dropped rather than failing the whole update.
+    pub fn set_media_metadata(&mut self, media: &Media) {
+        self.duration_seconds = media.duration.as_deref().and_then(parse_duration);
+        self.width = media.width.and_then(|width| u16::try_from(width).ok());
+        self.height = media.height.and_then(|height| u16::try_from(height).ok());
+        self.thumbnail_url = media
+            .thumbnail_url
+            .clone()
+            .or(media
+                .thumbnail
+                .as_ref()
+                .and_then(|thumbnail| thumbnail.url.clone()))
+            .filter(|url| !url.is_empty() && url.chars().count() <= MAX_THUMBNAIL_URL_LENGTH);
+        self.upload_date = media
+            .upload_date
+            .as_deref()
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.with_timezone(&Utc));
+    }
+
+    pub fn is_playable(&self) -> bool {
+        self.media_id.is_some()
+            && self.file_name.is_some()
+            && !self.is_hidden
+            && self.deleted_at.is_none()
+    }
+
+    pub fn delete(mut self, user: &User) -> Result<Self, ApplicationError> {
+        if !user.has_uploader_or_adm_role() {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        self.deleted_at = Some(Utc::now());
+
+        Ok(self)
+    }
+
     pub fn parse_to_grpc_model(self) -> Result<arkalis_service::Episode, ApplicationError> {
-        let ep =
-            arkalis_service::Episode {
-                id: self.id,
-                name: self.name,
-                cover_id: self.cover_id,
-                season_id: self.season_id,
-                source_id: self.source_id,
-                lbry_media_id: self.lbry_media_id.ok_or(ApplicationError::UnknownError(
-                    anyhow!("lbry_media_id is null"),
-                ))?,
-                file_name: self
-                    .file_name
-                    .ok_or(ApplicationError::UnknownError(anyhow!("file_name is null")))?,
-                is_nsfw: self.is_nsfw,
-                sequence: self.sequence as u32,
-            };
+        let ep = arkalis_service::Episode {
+            id: self.id,
+            name: self.name,
+            cover_id: self.cover_id,
+            season_id: self.season_id,
+            source_id: self.source_id,
+            lbry_media_id: self
+                .media_id
+                .ok_or(ApplicationError::UnknownError(anyhow!("media_id is null")))?,
+            media_provider: self.media_provider.to_i32().unwrap_or(0),
+            file_name: self
+                .file_name
+                .ok_or(ApplicationError::UnknownError(anyhow!("file_name is null")))?,
+            is_nsfw: self.is_nsfw,
+            sequence: self.sequence as u32,
+            duration_seconds: self.duration_seconds,
+            width: self.width.map(u32::from),
+            height: self.height.map(u32::from),
+            thumbnail_url: self.thumbnail_url,
+            upload_date: self.upload_date.map(|date| date.timestamp()),
+        };
 
         Ok(ep)
     }
@@ -121,42 +208,170 @@ impl Episode {
 
         Ok(res_eps)
     }
+}
 
-    fn get_lbry_media_id(url: &str) -> Result<String, ApplicationError> {
-        const LBRY_BASE_URL: &str = "https://open.lbry.com/";
-        if !url.starts_with(LBRY_BASE_URL) {
-            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
-                "Url do lbry incorreta",
-            )));
+/// Parses ISO 8601 durations such as `PT1H23M45S` into whole seconds, rounding fractions
+/// and refusing values that don't fit a `u32`.
+fn parse_duration(duration: &str) -> Option<u32> {
+    let time = duration
+        .strip_prefix("PT")
+        .filter(|time| !time.is_empty())?;
+    let mut seconds = 0f64;
+    let mut number = String::new();
+    for c in time.chars() {
+        match c {
+            '0'..='9' | '.' => number.push(c),
+            'H' | 'M' | 'S' => {
+                let value = number.parse::<f64>().ok()?;
+                let multiplier = match c {
+                    'H' => 3600.0,
+                    'M' => 60.0,
+                    _ => 1.0,
+                };
+                seconds += value * multiplier;
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+
+    let seconds = seconds.round();
+    (number.is_empty() && seconds <= f64::from(u32::MAX)).then_some(seconds as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view_models::odysee::Thumbnail;
+
+    fn episode() -> Episode {
+        Episode {
+            id: "episode".into(),
+            name: "episode".into(),
+            cover_id: None,
+            season_id: 1,
+            source_id: 1,
+            media_id: Some("media".into()),
+            media_provider: MediaProviderKind::Http,
+            file_name: None,
+            duration_seconds: None,
+            width: None,
+            height: None,
+            thumbnail_url: None,
+            upload_date: None,
+            is_nsfw: false,
+            sequence: 1,
+            is_hidden: false,
+            deleted_at: None,
         }
+    }
+
+    #[test]
+    fn parse_duration_reads_each_unit() {
+        assert_eq!(parse_duration("PT1H23M45S"), Some(5025));
+        assert_eq!(parse_duration("PT24M"), Some(1440));
+        assert_eq!(parse_duration("PT2H"), Some(7200));
+        assert_eq!(parse_duration("PT0S"), Some(0));
+    }
+
+    #[test]
+    fn parse_duration_rounds_fractional_values() {
+        assert_eq!(parse_duration("PT1420.6S"), Some(1421));
+        assert_eq!(parse_duration("PT23M40.2S"), Some(1420));
+        assert_eq!(parse_duration("PT1.5H"), Some(5400));
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflow() {
+        assert_eq!(parse_duration("PT4294967295S"), Some(u32::MAX));
+        assert_eq!(parse_duration("PT4294967296S"), None);
+        assert_eq!(parse_duration("PT1193047H"), None);
+    }
+
+    #[test]
+    fn parse_duration_rejects_malformed_values() {
+        assert_eq!(parse_duration("PT"), None);
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("P1D"), None);
+        assert_eq!(parse_duration("PTS"), None);
+        assert_eq!(parse_duration("PT12"), None);
+        assert_eq!(parse_duration("PT1.2.3S"), None);
+        assert_eq!(parse_duration("PT-5S"), None);
+    }
+
+    #[test]
+    fn set_media_metadata_copies_provider_metadata() {
+        let mut episode = episode();
+        episode.set_media_metadata(&Media {
+            duration: Some("PT24M".into()),
+            width: Some(1920),
+            height: Some(1080),
+            thumbnail_url: Some("https://example.com/thumb.jpg".into()),
+            upload_date: Some("2024-04-27T12:00:00Z".into()),
+            ..Default::default()
+        });
+
+        assert_eq!(episode.duration_seconds, Some(1440));
+        assert_eq!(episode.width, Some(1920));
+        assert_eq!(episode.height, Some(1080));
+        assert_eq!(
+            episode.thumbnail_url.as_deref(),
+            Some("https://example.com/thumb.jpg")
+        );
+        assert_eq!(
+            episode.upload_date.map(|date| date.timestamp()),
+            Some(1714219200)
+        );
+    }
+
+    #[test]
+    fn set_media_metadata_falls_back_to_the_thumbnail_object() {
+        let mut episode = episode();
+        episode.set_media_metadata(&Media {
+            thumbnail: Some(Thumbnail {
+                thumbnail_type: None,
+                url: Some("https://example.com/thumb.jpg".into()),
+            }),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            episode.thumbnail_url.as_deref(),
+            Some("https://example.com/thumb.jpg")
+        );
+    }
+
+    #[test]
+    fn set_media_metadata_drops_values_that_do_not_fit() {
+        let mut episode = episode();
+        episode.set_media_metadata(&Media {
+            duration: Some("PT".into()),
+            width: Some(i64::from(u16::MAX) + 1),
+            height: Some(-1),
+            thumbnail_url: Some(format!(
+                "https://example.com/{}",
+                "a".repeat(MAX_THUMBNAIL_URL_LENGTH)
+            )),
+            upload_date: Some("yesterday".into()),
+            ..Default::default()
+        });
+
+        assert_eq!(episode.duration_seconds, None);
+        assert_eq!(episode.width, None);
+        assert_eq!(episode.height, None);
+        assert_eq!(episode.thumbnail_url, None);
+        assert_eq!(episode.upload_date, None);
+        assert!(episode.validate().is_ok());
+    }
+
+    #[test]
+    fn set_media_metadata_drops_empty_thumbnails() {
+        let mut episode = episode();
+        episode.set_media_metadata(&Media {
+            thumbnail_url: Some(String::new()),
+            ..Default::default()
+        });
 
-        let media_id = url.replace(LBRY_BASE_URL, "");
-        Ok(media_id)
-    }
-
-    async fn get_file_name(media_id: &str) -> Result<String, ApplicationError> {
-        const ODYSEE_BASE_URL: &str = "https://odysee.com/";
-        let odysee_response = reqwest::get(format!("{}{}", ODYSEE_BASE_URL, media_id))
-            .await
-            .map_err(|e| ApplicationError::UnknownError(e.into()))?;
-        let odysee_response_body = odysee_response
-            .text()
-            .await
-            .map_err(|e| ApplicationError::UnknownError(e.into()))?;
-        let regex = regex::Regex::new(r#"(?s)<script type="application/ld\+json">(.*?)</script>"#)
-            .map_err(|e| ApplicationError::UnknownError(e.into()))?;
-        let caps =
-            regex
-                .captures(odysee_response_body.as_str())
-                .ok_or(ApplicationError::UnknownError(anyhow!(
-                    "Failed to parse html for data"
-                )))?;
-        let json =
-            serde_json::from_str::<view_models::odysee::Media>(caps.get(1).unwrap().as_str())
-                .map_err(|e| ApplicationError::UnknownError(e.into()))?;
-        json.content_url
-            .ok_or(ApplicationError::UnknownError(anyhow!(
-                "Failed to get file_name from odysee response"
-            )))
+        assert_eq!(episode.thumbnail_url, None);
     }
 }
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::models::media_resolution_error::MediaResolutionError;
+
+const MAX_LAST_ERROR_LENGTH: usize = 500;
+
+#[derive(FromRow)]
+pub struct EpisodeHealthCheck {
+    pub episode_id: String,
+    pub is_healthy: bool,
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+    pub auto_hidden: bool,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl EpisodeHealthCheck {
+    pub fn healthy(episode_id: String) -> Self {
+        Self {
+            episode_id,
+            is_healthy: true,
+            last_error: None,
+            consecutive_failures: 0,
+            auto_hidden: false,
+            checked_at: Utc::now(),
+        }
+    }
+
+    pub fn failed(
+        episode_id: String,
+        previous: Option<&EpisodeHealthCheck>,
+        error: &MediaResolutionError,
+    ) -> Self {
+        let consecutive_failures = previous.map_or(0, |check| check.consecutive_failures);
+        // Timeouts and outages say nothing about the media itself, so they don't count towards hiding it.
+        let consecutive_failures = if error.is_retryable() {
+            consecutive_failures
+        } else {
+            consecutive_failures + 1
+        };
+
+        let last_error = error
+            .to_string()
+            .chars()
+            .take(MAX_LAST_ERROR_LENGTH)
+            .collect();
+
+        Self {
+            episode_id,
+            is_healthy: false,
+            last_error: Some(last_error),
+            consecutive_failures,
+            auto_hidden: previous.is_some_and(|check| check.auto_hidden),
+            checked_at: Utc::now(),
+        }
+    }
+}
@@ -2,6 +2,9 @@ use thiserror::Error;
 use tonic::{Code, Status};
 use validator::ValidationErrors;
 
+use crate::models::media_resolution_error::MediaResolutionError;
+use crate::models::metadata_provider_error::MetadataProviderError;
+
 #[derive(Error, Debug)]
 pub enum ApplicationError {
     #[error("Unknown error")]
@@ -14,6 +17,12 @@ pub enum ApplicationError {
     InvalidData(anyhow::Error),
     #[error("Entity not found")]
     NotFound,
+    #[error("Too many requests")]
+    TooManyRequests,
+    #[error("Media resolution failed")]
+    MediaResolution(#[from] MediaResolutionError),
+    #[error("Metadata fetch failed")]
+    MetadataProvider(#[from] MetadataProviderError),
 }
 
 impl From<ApplicationError> for Status {
@@ -28,6 +37,26 @@ impl From<ApplicationError> for Status {
                 Status::new(Code::InvalidArgument, err.to_string())
             }
             ApplicationError::NotFound => Status::new(Code::NotFound, value.to_string()),
+            ApplicationError::TooManyRequests => {
+                Status::new(Code::ResourceExhausted, value.to_string())
+            }
+            ApplicationError::MediaResolution(err) => {
+                let code = match err {
+                    MediaResolutionError::NotFound(_) => Code::FailedPrecondition,
+                    MediaResolutionError::Timeout => Code::DeadlineExceeded,
+                    MediaResolutionError::Unavailable(_) => Code::Unavailable,
+                    MediaResolutionError::InvalidResponse(_) => Code::Internal,
+                };
+                Status::new(code, err.to_string())
+            }
+            ApplicationError::MetadataProvider(err) => {
+                let code = match err {
+                    MetadataProviderError::Timeout => Code::DeadlineExceeded,
+                    MetadataProviderError::Unavailable(_) => Code::Unavailable,
+                    MetadataProviderError::InvalidResponse(_) => Code::Internal,
+                };
+                Status::new(code, err.to_string())
+            }
         }
     }
 }
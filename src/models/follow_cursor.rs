@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::cursor::Cursor;
+
+#[derive(Serialize, Deserialize)]
+pub struct FollowCursor {
+    pub created_at: i64,
+    pub anime_id: u32,
+}
+
+impl Cursor for FollowCursor {}
@@ -1,8 +1,10 @@
 use bitflags::bitflags;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy)]
 pub struct Genre(u64);
 
 bitflags! {
@@ -63,3 +65,16 @@ impl Display for Genre {
         write!(f, "{name}")
     }
 }
+
+impl Serialize for Genre {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.bits())
+    }
+}
+
+impl<'de> Deserialize<'de> for Genre {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = u64::deserialize(deserializer)?;
+        Genre::from_bits(bits).ok_or(D::Error::custom("genre is not a valid genre flag"))
+    }
+}
@@ -0,0 +1,19 @@
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive;
+use serde::Serialize;
+
+#[derive(PartialEq, Eq, Clone, Copy, FromPrimitive, ToPrimitive, Serialize)]
+#[repr(u8)]
+pub enum MediaProviderKind {
+    Lbry,
+    Http,
+    ObjectStorage,
+}
+
+impl TryFrom<u8> for MediaProviderKind {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::from_u8(value).ok_or(format!("{} is not a valid media provider", value))
+    }
+}
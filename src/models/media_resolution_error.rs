@@ -0,0 +1,34 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MediaResolutionError {
+    #[error("Media not found: {0}")]
+    NotFound(String),
+    #[error("Media provider timed out")]
+    Timeout,
+    #[error("Media provider is unavailable: {0}")]
+    Unavailable(String),
+    #[error("Media provider returned an invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+impl MediaResolutionError {
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            MediaResolutionError::Timeout | MediaResolutionError::Unavailable(_)
+        )
+    }
+}
+
+impl From<reqwest::Error> for MediaResolutionError {
+    fn from(value: reqwest::Error) -> Self {
+        if value.is_timeout() {
+            MediaResolutionError::Timeout
+        } else if value.is_decode() || value.is_redirect() {
+            MediaResolutionError::InvalidResponse(value.to_string())
+        } else {
+            MediaResolutionError::Unavailable(value.to_string())
+        }
+    }
+}
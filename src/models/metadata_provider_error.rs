@@ -0,0 +1,23 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MetadataProviderError {
+    #[error("Metadata provider timed out")]
+    Timeout,
+    #[error("Metadata provider is unavailable: {0}")]
+    Unavailable(String),
+    #[error("Metadata provider returned an invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+impl From<reqwest::Error> for MetadataProviderError {
+    fn from(value: reqwest::Error) -> Self {
+        if value.is_timeout() {
+            MetadataProviderError::Timeout
+        } else if value.is_decode() {
+            MetadataProviderError::InvalidResponse(value.to_string())
+        } else {
+            MetadataProviderError::Unavailable(value.to_string())
+        }
+    }
+}
@@ -1,15 +1,48 @@
 pub mod anime;
-mod anime_in_anime_list;
-mod anime_list;
+pub mod anime_cursor;
+pub mod anime_in_anime_list;
+pub mod anime_list;
+pub mod anime_list_entry;
+pub mod anime_list_entry_cursor;
+pub mod anime_metadata;
+pub mod anime_rating;
+pub mod anime_revision;
+pub mod anime_sort_key;
 pub mod arguments;
+pub mod audit_event;
+pub mod audit_event_cursor;
+pub mod broken_episode;
+pub mod broken_episode_cursor;
+pub mod comment;
+pub mod comment_cursor;
+pub mod comment_moderation_action;
 pub mod config;
+pub mod credentials;
+pub mod cursor;
+pub mod entity_type;
 pub mod episode;
+pub mod episode_health_check;
 pub mod error;
-mod genre;
+pub mod follow_cursor;
+pub mod genre;
+pub mod media_provider_kind;
+pub mod media_resolution_error;
+pub mod metadata_provider_error;
+pub mod notification;
+pub mod notification_cursor;
+pub mod page;
+pub mod refresh_token;
 pub mod roles;
+pub mod search_text;
 pub mod season;
+pub mod secret_hash;
+pub mod session;
 pub mod source;
 pub mod source_type;
 mod title;
 mod title_type;
 pub mod user;
+pub mod user_cursor;
+pub mod watch_list_import;
+pub mod watch_progress;
+pub mod watch_status;
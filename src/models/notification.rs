@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use sqlx::mysql::MySqlRow;
+use sqlx::{Error, FromRow, Row};
+
+use crate::arkalis_service;
+
+pub struct Notification {
+    pub id: u64,
+    pub user_id: String,
+    pub anime_id: u32,
+    pub anime_title: String,
+    pub episode_id: String,
+    pub season_id: u32,
+    pub sequence: u16,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+impl FromRow<'_, MySqlRow> for Notification {
+    fn from_row(row: &'_ MySqlRow) -> Result<Self, Error> {
+        let notification = Self {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            anime_id: row.try_get("anime_id")?,
+            anime_title: row.try_get("main_title")?,
+            episode_id: row.try_get("episode_id")?,
+            season_id: row.try_get("season_id")?,
+            sequence: row.try_get("sequence")?,
+            created_at: row.try_get("created_at")?,
+            read_at: row.try_get("read_at")?,
+        };
+
+        Ok(notification)
+    }
+}
+
+impl From<Notification> for arkalis_service::Notification {
+    fn from(value: Notification) -> Self {
+        Self {
+            id: value.id,
+            anime_id: value.anime_id,
+            anime_title: value.anime_title,
+            episode_id: value.episode_id,
+            season_id: value.season_id,
+            sequence: value.sequence as u32,
+            created_at: value.created_at.timestamp(),
+            read_at: value.read_at.map(|read_at| read_at.timestamp()),
+        }
+    }
+}
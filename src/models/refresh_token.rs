@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::session::Session;
+
+#[derive(FromRow)]
+pub struct RefreshToken {
+    pub id: String,
+    pub session_id: String,
+    pub token_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl RefreshToken {
+    pub fn new(session: &Session) -> (Self, String) {
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+
+        let refresh_token = Self {
+            id: Uuid::new_v4().to_string(),
+            session_id: session.id.clone(),
+            token_hash: Self::hash(&token),
+            created_at: Utc::now(),
+            expires_at: session.expires_at,
+            used_at: None,
+            revoked_at: None,
+        };
+
+        (refresh_token, token)
+    }
+
+    pub fn hash(token: &str) -> String {
+        format!("{:x}", Sha256::digest(token.as_bytes()))
+    }
+
+    pub fn is_reused(&self) -> bool {
+        self.used_at.is_some() || self.revoked_at.is_some()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
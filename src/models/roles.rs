@@ -1,6 +1,9 @@
 use num_derive::{FromPrimitive, ToPrimitive};
+use serde::Serialize;
 
-#[derive(PartialEq, Eq, Clone, Copy, FromPrimitive, ToPrimitive)]
+use crate::models::error::ApplicationError;
+
+#[derive(PartialEq, Eq, Clone, Copy, FromPrimitive, ToPrimitive, Serialize)]
 #[repr(u8)]
 pub enum Roles {
     Admin,
@@ -8,6 +11,19 @@ pub enum Roles {
     User,
 }
 
+impl Roles {
+    pub fn parse(value: &str) -> Result<Self, ApplicationError> {
+        match value {
+            "admin" => Ok(Roles::Admin),
+            "uploader" => Ok(Roles::Uploader),
+            "user" => Ok(Roles::User),
+            _ => Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "role is invalid",
+            ))),
+        }
+    }
+}
+
 impl From<Roles> for String {
     fn from(value: Roles) -> Self {
         match value {
@@ -0,0 +1,29 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+const MIN_TOKEN_LENGTH: usize = 3;
+
+pub fn normalize(text: &str) -> String {
+    text.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn to_boolean_query(text: &str) -> Option<String> {
+    let terms = normalize(text)
+        .split(' ')
+        .filter(|term| term.chars().count() >= MIN_TOKEN_LENGTH)
+        .map(|term| format!("+{}*", term))
+        .collect::<Vec<_>>();
+
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(terms.join(" "))
+}
@@ -4,11 +4,14 @@ use crate::extensions::OptionToAppResult;
 use crate::models::error::ApplicationError;
 use crate::models::roles::Roles;
 use crate::models::user::User;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use sqlx::FromRow;
 use validator::Validate;
 
-#[derive(Validate, FromRow)]
+#[derive(Validate, FromRow, Serialize)]
 pub struct Season {
+    #[serde(skip)]
     pub id: Option<u32>,
     #[validate(length(min = 1, max = 255))]
     pub name: String,
@@ -16,6 +19,7 @@ pub struct Season {
     pub cover_id: Option<String>,
     pub anime_id: u32,
     pub sequence: u16,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl Season {
@@ -30,6 +34,7 @@ impl Season {
             sequence: season_request.sequence as u16,
             name: season_request.name,
             id: None,
+            deleted_at: None,
         };
 
         Ok(season)
@@ -56,6 +61,16 @@ impl Season {
 
         Ok(self)
     }
+
+    pub fn delete(mut self, user: &User) -> Result<Self, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        self.deleted_at = Some(Utc::now());
+
+        Ok(self)
+    }
 }
 
 impl From<Season> for arkalis_service::Season {
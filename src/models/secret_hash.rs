@@ -0,0 +1,60 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand::distributions::{Alphanumeric, DistString};
+use rand::rngs::OsRng;
+use std::sync::OnceLock;
+
+use crate::models::error::ApplicationError;
+
+/// Argon2 is deliberately slow, so hashing and verification run on the blocking pool
+/// instead of stalling the runtime workers.
+pub async fn hash(secret: &str) -> Result<String, ApplicationError> {
+    let secret = secret.to_string();
+    tokio::task::spawn_blocking(move || hash_blocking(&secret))
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+}
+
+pub async fn verify(secret: &str, hash: &str) -> bool {
+    let (secret, hash) = (secret.to_string(), hash.to_string());
+    tokio::task::spawn_blocking(move || verify_blocking(&secret, &hash))
+        .await
+        .unwrap_or(false)
+}
+
+/// Burns the same time as a real verification, so a lookup miss can't be told apart
+/// from a wrong secret.
+pub async fn verify_dummy(secret: &str) {
+    static DUMMY_HASH: OnceLock<Option<String>> = OnceLock::new();
+
+    let secret = secret.to_string();
+    let _ = tokio::task::spawn_blocking(move || {
+        let dummy_hash = DUMMY_HASH.get_or_init(|| hash_blocking(&random_string(32)).ok());
+        if let Some(dummy_hash) = dummy_hash {
+            verify_blocking(&secret, dummy_hash);
+        }
+    })
+    .await;
+}
+
+pub fn random_string(len: usize) -> String {
+    Alphanumeric.sample_string(&mut OsRng, len)
+}
+
+fn hash_blocking(secret: &str) -> Result<String, ApplicationError> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(secret.as_bytes(), &salt)
+        .map_err(|e| ApplicationError::UnknownError(anyhow::Error::msg(e.to_string())))?;
+    Ok(hash.to_string())
+}
+
+fn verify_blocking(secret: &str, hash: &str) -> bool {
+    let Ok(hash) = PasswordHash::new(hash) else {
+        return false;
+    };
+
+    Argon2::default()
+        .verify_password(secret.as_bytes(), &hash)
+        .is_ok()
+}
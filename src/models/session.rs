@@ -0,0 +1,61 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::arkalis_service;
+use crate::models::config::Config;
+use crate::models::error::ApplicationError;
+use crate::models::roles::Roles;
+use crate::models::user::User;
+
+const DEFAULT_REFRESH_TOKEN_TTL_SECONDS: i64 = 60 * 60 * 24 * 30;
+
+#[derive(FromRow)]
+pub struct Session {
+    pub id: String,
+    pub user_id: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl Session {
+    pub fn new(user: &User, config: &Config) -> Self {
+        let ttl = config
+            .refresh_token_ttl_seconds
+            .unwrap_or(DEFAULT_REFRESH_TOKEN_TTL_SECONDS);
+        let created_at = Utc::now();
+
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: user.id.clone(),
+            created_at,
+            expires_at: created_at + Duration::seconds(ttl),
+            revoked_at: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > Utc::now()
+    }
+
+    pub fn revoke(mut self, user: &User) -> Result<Self, ApplicationError> {
+        if self.user_id != user.id && user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        self.revoked_at = Some(Utc::now());
+
+        Ok(self)
+    }
+}
+
+impl From<Session> for arkalis_service::Session {
+    fn from(value: Session) -> Self {
+        Self {
+            id: value.id,
+            created_at: value.created_at.timestamp(),
+            expires_at: value.expires_at.timestamp(),
+        }
+    }
+}
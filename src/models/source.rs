@@ -1,4 +1,6 @@
 use crate::arkalis_service;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use sqlx::mysql::MySqlRow;
 use sqlx::{Error, FromRow, Row};
 use validator::Validate;
@@ -10,13 +12,15 @@ use crate::models::roles::Roles;
 use crate::models::source_type::SourceType;
 use crate::models::user::User;
 
-#[derive(Validate)]
+#[derive(Validate, Serialize)]
 pub struct Source {
+    #[serde(skip)]
     pub id: Option<u32>,
     #[validate(length(min = 1, max = 255))]
     pub name: String,
     pub source_type: SourceType,
     pub priority: u8,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl Source {
@@ -35,6 +39,7 @@ impl Source {
             name: data.name,
             source_type,
             priority: data.priority as u8,
+            deleted_at: None,
         };
 
         Ok(source)
@@ -62,6 +67,16 @@ impl Source {
 
         Ok(self)
     }
+
+    pub fn delete(mut self, user: &User) -> Result<Self, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        self.deleted_at = Some(Utc::now());
+
+        Ok(self)
+    }
 }
 
 impl FromRow<'_, MySqlRow> for Source {
@@ -76,6 +91,7 @@ impl FromRow<'_, MySqlRow> for Source {
             name: row.try_get("name")?,
             source_type,
             priority: row.try_get("priority")?,
+            deleted_at: row.try_get("deleted_at")?,
         };
 
         Ok(source)
@@ -1,6 +1,7 @@
 use std::fmt::{Display, Formatter};
 
 use bitflags::bitflags;
+use serde::{Serialize, Serializer};
 
 #[derive(PartialEq, Eq)]
 pub struct SourceType(u64);
@@ -33,3 +34,9 @@ impl Display for SourceType {
         write!(f, "{name}")
     }
 }
+
+impl Serialize for SourceType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.bits())
+    }
+}
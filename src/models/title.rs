@@ -5,7 +5,7 @@ use num_traits::{FromPrimitive, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-#[derive(Validate, Serialize, Deserialize)]
+#[derive(Validate, Clone, Serialize, Deserialize)]
 pub struct Title {
     #[validate(length(min = 1, max = 1024))]
     pub name: String,
@@ -1,31 +1,55 @@
-use std::collections::BTreeMap;
-
+use chrono::{Duration, Utc};
 use hmac::digest::core_api::CoreWrapper;
 use hmac::{Hmac, HmacCore};
-use jwt::{Header, SignWithKey, Token, VerifyWithKey};
-use khash::Digest;
+use jwt::{Claims, Header, RegisteredClaims, SignWithKey, Token, VerifyWithKey};
 use num_traits::FromPrimitive;
+use regex::Regex;
+use serde::Serialize;
 use sha2::digest::Mac;
 use sha2::Sha256;
 use sqlx::mysql::MySqlRow;
 use sqlx::{Error, FromRow, Row};
+use std::sync::OnceLock;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::arkalis_service;
 use crate::arkalis_service::{CreateTokenRequest, GetUserInfoResponse};
 use crate::models::config::Config;
 use crate::models::error::ApplicationError;
 use crate::models::roles::Roles;
+use crate::models::secret_hash;
+use crate::models::session::Session;
+
+const DEFAULT_TOKEN_TTL_SECONDS: i64 = 60 * 15;
+const RECOVERY_KEY_PREFIX_LENGTH: usize = 8;
+const RECOVERY_KEY_SECRET_LENGTH: usize = 40;
+const MAL_PROFILE_PATTERN: &str =
+    r"^(?:https?://(?:www\.)?myanimelist\.net/profile/)?([A-Za-z0-9_-]{2,16})/?$";
+const ANILIST_PROFILE_PATTERN: &str =
+    r"^(?:https?://(?:www\.)?anilist\.co/user/)?([A-Za-z0-9]{2,20})/?$";
+
+static MAL_PROFILE_REGEX: OnceLock<Regex> = OnceLock::new();
+static ANILIST_PROFILE_REGEX: OnceLock<Regex> = OnceLock::new();
 
-#[derive(Validate, Clone)]
+#[derive(Validate, Clone, Serialize)]
 pub struct User {
+    #[serde(skip)]
     pub id: String,
     #[validate(length(min = 4))]
     pub display_name: String,
     pub role: Roles,
+    #[serde(skip)]
     pub mal_profile: Option<String>,
+    #[serde(skip)]
     pub anilist_profile: Option<String>,
+    #[serde(skip)]
     pub recovery_key: Option<String>,
+    #[serde(skip)]
+    pub recovery_key_prefix: Option<String>,
+    pub is_banned: bool,
+    #[serde(skip)]
+    pub session_id: Option<String>,
 }
 
 impl User {
@@ -38,22 +62,47 @@ impl User {
             mal_profile: None,
             anilist_profile: None,
             recovery_key: None,
+            recovery_key_prefix: None,
+            is_banned: false,
+            session_id: None,
         }
     }
 
-    pub fn generate_token(self, config: &Config) -> Result<String, ApplicationError> {
+    pub fn generate_token(
+        self,
+        session: &Session,
+        config: &Config,
+    ) -> Result<String, ApplicationError> {
         let key = Self::get_jwt_key(config)?;
-        let mut claims = BTreeMap::new();
-        claims.insert("id", self.id);
-        claims.insert("display_name", self.display_name);
-        claims.insert("role", self.role.into());
+        let issued_at = Utc::now();
+        let ttl = config
+            .token_ttl_seconds
+            .unwrap_or(DEFAULT_TOKEN_TTL_SECONDS);
+        let expiration = (issued_at + Duration::seconds(ttl)).min(session.expires_at);
+        let mut claims = Claims::new(RegisteredClaims {
+            issued_at: Some(issued_at.timestamp() as u64),
+            expiration: Some(expiration.timestamp() as u64),
+            json_web_token_id: Some(session.id.clone()),
+            ..Default::default()
+        });
+        claims.private.insert("id".into(), self.id.into());
+        claims
+            .private
+            .insert("display_name".into(), self.display_name.into());
+        claims
+            .private
+            .insert("role".into(), String::from(self.role).into());
 
         if let Some(mal_profile) = self.mal_profile {
-            claims.insert("mal_profile", mal_profile);
+            claims
+                .private
+                .insert("mal_profile".into(), mal_profile.into());
         }
 
         if let Some(anilist_profile) = self.anilist_profile {
-            claims.insert("anilist_profile", anilist_profile);
+            claims
+                .private
+                .insert("anilist_profile".into(), anilist_profile.into());
         }
 
         claims.sign_with_key(&key).map_err(|_| {
@@ -64,26 +113,68 @@ impl User {
     pub fn from_token(token: String, config: &Config) -> Result<Self, ApplicationError> {
         let key = Self::get_jwt_key(config)?;
 
-        let token: Token<Header, BTreeMap<String, String>, _> = token
+        let token: Token<Header, Claims, _> = token
             .verify_with_key(&key)
             .map_err(|_| ApplicationError::Unauthorized)?;
         let claims = token.claims();
 
-        let mal_profile = claims.get("mal_profile").cloned();
-        let anilist_profile = claims.get("anilist_profile").cloned();
+        let expiration = claims
+            .registered
+            .expiration
+            .ok_or(ApplicationError::Unauthorized)?;
+        if expiration <= Utc::now().timestamp() as u64 {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let session_id = claims
+            .registered
+            .json_web_token_id
+            .clone()
+            .ok_or(ApplicationError::Unauthorized)?;
+
+        let claim = |name: &str| {
+            claims
+                .private
+                .get(name)
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string())
+        };
 
         let user = Self {
-            id: claims["id"].clone(),
-            display_name: claims["display_name"].clone(),
-            role: Roles::from(claims["role"].as_str()),
-            mal_profile,
-            anilist_profile,
+            id: claim("id").ok_or(ApplicationError::Unauthorized)?,
+            display_name: claim("display_name").ok_or(ApplicationError::Unauthorized)?,
+            role: Roles::from(claim("role").unwrap_or_default().as_str()),
+            mal_profile: claim("mal_profile"),
+            anilist_profile: claim("anilist_profile"),
             recovery_key: None,
+            recovery_key_prefix: None,
+            is_banned: false,
+            session_id: Some(session_id),
         };
 
         Ok(user)
     }
 
+    /// Returns the user id of a token issued before sessions existed, which carries no `exp` or `jti`.
+    pub fn legacy_token_user_id(token: &str, config: &Config) -> Result<String, ApplicationError> {
+        let key = Self::get_jwt_key(config)?;
+
+        let token: Token<Header, Claims, _> = token
+            .verify_with_key(&key)
+            .map_err(|_| ApplicationError::Unauthorized)?;
+        let claims = token.claims();
+        if claims.registered.expiration.is_some() || claims.registered.json_web_token_id.is_some() {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        claims
+            .private
+            .get("id")
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string())
+            .ok_or(ApplicationError::Unauthorized)
+    }
+
     pub fn create_adm_user(
         config: &Config,
         display_name: String,
@@ -102,12 +193,99 @@ impl User {
         self.role == Roles::Uploader || self.role == Roles::Admin
     }
 
-    pub fn get_recovery_mnemonic(&mut self) -> String {
-        if self.recovery_key.is_none() {
-            self.recovery_key = Some(Digest::new(&mut self.id.as_bytes()).collect());
+    pub fn set_role(mut self, role: &str, admin: &User) -> Result<Self, ApplicationError> {
+        self.check_managed_by(admin)?;
+        self.role = Roles::parse(role)?;
+
+        Ok(self)
+    }
+
+    pub fn set_ban(mut self, is_banned: bool, admin: &User) -> Result<Self, ApplicationError> {
+        self.check_managed_by(admin)?;
+        self.is_banned = is_banned;
+
+        Ok(self)
+    }
+
+    pub fn rename(mut self, display_name: String, admin: &User) -> Result<Self, ApplicationError> {
+        if admin.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        self.display_name = display_name;
+
+        Ok(self)
+    }
+
+    fn check_managed_by(&self, admin: &User) -> Result<(), ApplicationError> {
+        if admin.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        if admin.id == self.id {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "admins cannot change their own role or ban status",
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn update_profile(
+        mut self,
+        mal_profile: Option<String>,
+        anilist_profile: Option<String>,
+    ) -> Result<Self, ApplicationError> {
+        if let Some(mal_profile) = mal_profile {
+            self.mal_profile = parse_profile(
+                &mal_profile,
+                profile_regex(&MAL_PROFILE_REGEX, MAL_PROFILE_PATTERN),
+                "mal_profile",
+            )?;
+        }
+
+        if let Some(anilist_profile) = anilist_profile {
+            self.anilist_profile = parse_profile(
+                &anilist_profile,
+                profile_regex(&ANILIST_PROFILE_REGEX, ANILIST_PROFILE_PATTERN),
+                "anilist_profile",
+            )?;
+        }
+
+        Ok(self)
+    }
+
+    pub async fn generate_recovery_key(&mut self) -> Result<String, ApplicationError> {
+        let prefix = secret_hash::random_string(RECOVERY_KEY_PREFIX_LENGTH);
+        let recovery_key = format!(
+            "{}.{}",
+            prefix,
+            secret_hash::random_string(RECOVERY_KEY_SECRET_LENGTH)
+        );
+
+        self.recovery_key = Some(secret_hash::hash(&recovery_key).await?);
+        self.recovery_key_prefix = Some(prefix);
+
+        Ok(recovery_key)
+    }
+
+    pub async fn verify_recovery_key(&self, recovery_key: &str) -> bool {
+        match &self.recovery_key {
+            Some(hash) => secret_hash::verify(recovery_key, hash).await,
+            None => false,
+        }
+    }
+
+    pub fn recovery_key_prefix(recovery_key: &str) -> Result<String, ApplicationError> {
+        let Some((prefix, _)) = recovery_key.split_once('.') else {
+            return Err(ApplicationError::Unauthorized);
+        };
+
+        if prefix.len() != RECOVERY_KEY_PREFIX_LENGTH {
+            return Err(ApplicationError::Unauthorized);
         }
 
-        self.recovery_key.as_ref().unwrap().clone()
+        Ok(prefix.to_string())
     }
 
     fn get_jwt_key(config: &Config) -> Result<CoreWrapper<HmacCore<Sha256>>, ApplicationError> {
@@ -119,6 +297,30 @@ impl User {
     }
 }
 
+fn profile_regex(regex: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    regex.get_or_init(|| Regex::new(pattern).expect("profile patterns are valid"))
+}
+
+fn parse_profile(
+    value: &str,
+    regex: &Regex,
+    field: &'static str,
+) -> Result<Option<String>, ApplicationError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    let caps = regex
+        .captures(value)
+        .ok_or(ApplicationError::InvalidData(anyhow::Error::msg(format!(
+            "{} is not a valid username or profile url",
+            field
+        ))))?;
+
+    Ok(Some(caps[1].to_string()))
+}
+
 impl From<CreateTokenRequest> for User {
     fn from(request: CreateTokenRequest) -> Self {
         Self::new(request.display_name)
@@ -133,6 +335,18 @@ impl From<User> for GetUserInfoResponse {
             role: value.role.into(),
             mal_profile: value.mal_profile,
             anilist_profile: value.anilist_profile,
+            list_counts: Vec::new(),
+        }
+    }
+}
+
+impl From<User> for arkalis_service::UserInfo {
+    fn from(value: User) -> Self {
+        Self {
+            id: value.id,
+            display_name: value.display_name,
+            role: value.role.into(),
+            is_banned: value.is_banned,
         }
     }
 }
@@ -147,9 +361,12 @@ impl FromRow<'_, MySqlRow> for User {
             id: row.try_get("id")?,
             display_name: row.try_get("display_name")?,
             role,
-            mal_profile: None,
-            anilist_profile: None,
+            mal_profile: row.try_get("mal_profile")?,
+            anilist_profile: row.try_get("anilist_profile")?,
             recovery_key: row.try_get("recovery_key")?,
+            recovery_key_prefix: row.try_get("recovery_key_prefix")?,
+            is_banned: row.try_get("is_banned")?,
+            session_id: None,
         };
 
         Ok(user)
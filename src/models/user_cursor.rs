@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::cursor::Cursor;
+
+#[derive(Serialize, Deserialize)]
+pub struct UserCursor {
+    pub display_name: String,
+    pub id: String,
+}
+
+impl Cursor for UserCursor {}
@@ -0,0 +1,217 @@
+use num_derive::FromPrimitive;
+
+use crate::arkalis_service;
+use crate::models::anime_list::AnimeList;
+use crate::models::error::ApplicationError;
+use crate::models::watch_status::WatchStatus;
+use crate::view_models::anilist_export::{AniListExport, AniListScoreFormat};
+use crate::view_models::mal_export::MalExport;
+
+#[derive(Clone, Copy, FromPrimitive)]
+#[repr(u8)]
+pub enum ImportFormat {
+    MalXml,
+    AniListJson,
+}
+
+pub struct ImportedEntry {
+    pub anime_list: AnimeList,
+    pub id_in_list: String,
+    pub title: Option<String>,
+    pub status: Option<WatchStatus>,
+    pub score: Option<u8>,
+    pub progress: Option<u32>,
+}
+
+impl ImportedEntry {
+    pub fn into_grpc(self, anime_id: Option<u32>) -> arkalis_service::ImportedEntry {
+        arkalis_service::ImportedEntry {
+            anime_list: self.anime_list as i32,
+            id_in_list: self.id_in_list,
+            title: self.title,
+            anime_id,
+            status: self.status.map(|status| status as i32),
+        }
+    }
+}
+
+impl ImportFormat {
+    pub fn anime_list(&self) -> AnimeList {
+        match self {
+            ImportFormat::MalXml => AnimeList::MyAnimeList,
+            ImportFormat::AniListJson => AnimeList::AniList,
+        }
+    }
+}
+
+pub fn parse(format: ImportFormat, content: &str) -> Result<Vec<ImportedEntry>, ApplicationError> {
+    match format {
+        ImportFormat::MalXml => parse_mal(content),
+        ImportFormat::AniListJson => parse_anilist(content),
+    }
+}
+
+fn parse_mal(content: &str) -> Result<Vec<ImportedEntry>, ApplicationError> {
+    let export: MalExport = quick_xml::de::from_str(content)
+        .map_err(|e| ApplicationError::InvalidData(anyhow::Error::msg(e.to_string())))?;
+
+    let entries = export
+        .anime
+        .into_iter()
+        .map(|anime| ImportedEntry {
+            anime_list: AnimeList::MyAnimeList,
+            id_in_list: anime.series_animedb_id.to_string(),
+            title: anime.series_title,
+            status: WatchStatus::from_mal(&anime.my_status),
+            score: anime.my_score.filter(|score| *score > 0),
+            progress: anime.my_watched_episodes,
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+fn parse_anilist(content: &str) -> Result<Vec<ImportedEntry>, ApplicationError> {
+    let export: AniListExport = serde_json::from_str(content)
+        .map_err(|e| ApplicationError::InvalidData(anyhow::Error::msg(e.to_string())))?;
+    let collection = match export {
+        AniListExport::Query { data } => data.media_list_collection,
+        AniListExport::Collection(collection) => collection,
+    };
+
+    // Scores are exported in the user's own scale, which defaults to POINT_10 on AniList.
+    let score_format = collection
+        .user
+        .and_then(|user| user.media_list_options)
+        .and_then(|options| options.score_format)
+        .unwrap_or_default();
+
+    let entries = collection
+        .lists
+        .into_iter()
+        .flat_map(|list| list.entries)
+        .map(|entry| {
+            let title = entry
+                .media
+                .and_then(|media| media.title)
+                .and_then(|title| title.romaji.or(title.english));
+
+            ImportedEntry {
+                anime_list: AnimeList::AniList,
+                id_in_list: entry.media_id.to_string(),
+                title,
+                status: WatchStatus::from_anilist(&entry.status),
+                score: entry
+                    .score
+                    .and_then(|score| normalize_anilist_score(score, score_format)),
+                progress: entry.progress,
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+fn normalize_anilist_score(score: f64, format: AniListScoreFormat) -> Option<u8> {
+    let score = match format {
+        AniListScoreFormat::Point100 => score / 10.0,
+        AniListScoreFormat::Point10Decimal | AniListScoreFormat::Point10 => score,
+        AniListScoreFormat::Point5 => score * 2.0,
+        AniListScoreFormat::Point3 => score * 10.0 / 3.0,
+    };
+    let score = score.round().clamp(0.0, 10.0) as u8;
+    Some(score).filter(|score| *score > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_anilist_score_scales_each_format() {
+        assert_eq!(
+            normalize_anilist_score(85.0, AniListScoreFormat::Point100),
+            Some(9)
+        );
+        assert_eq!(
+            normalize_anilist_score(7.5, AniListScoreFormat::Point10Decimal),
+            Some(8)
+        );
+        assert_eq!(
+            normalize_anilist_score(7.0, AniListScoreFormat::Point10),
+            Some(7)
+        );
+        assert_eq!(
+            normalize_anilist_score(3.0, AniListScoreFormat::Point5),
+            Some(6)
+        );
+        assert_eq!(
+            normalize_anilist_score(1.0, AniListScoreFormat::Point3),
+            Some(3)
+        );
+        assert_eq!(
+            normalize_anilist_score(2.0, AniListScoreFormat::Point3),
+            Some(7)
+        );
+        assert_eq!(
+            normalize_anilist_score(3.0, AniListScoreFormat::Point3),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn normalize_anilist_score_keeps_low_scores_on_the_100_point_scale() {
+        assert_eq!(
+            normalize_anilist_score(8.0, AniListScoreFormat::Point100),
+            Some(1)
+        );
+        assert_eq!(
+            normalize_anilist_score(100.0, AniListScoreFormat::Point100),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn normalize_anilist_score_treats_zero_as_unscored() {
+        assert_eq!(
+            normalize_anilist_score(0.0, AniListScoreFormat::Point100),
+            None
+        );
+        assert_eq!(
+            normalize_anilist_score(0.0, AniListScoreFormat::Point3),
+            None
+        );
+        assert_eq!(
+            normalize_anilist_score(4.0, AniListScoreFormat::Point100),
+            None
+        );
+    }
+
+    #[test]
+    fn normalize_anilist_score_clamps_out_of_range_scores() {
+        assert_eq!(
+            normalize_anilist_score(42.0, AniListScoreFormat::Point10),
+            Some(10)
+        );
+        assert_eq!(
+            normalize_anilist_score(-3.0, AniListScoreFormat::Point10),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_anilist_reads_the_score_format() {
+        let content = r#"{"data":{"MediaListCollection":{"user":{"mediaListOptions":{"scoreFormat":"POINT_5"}},"lists":[{"entries":[{"mediaId":1,"status":"COMPLETED","score":4,"progress":12}]}]}}}"#;
+        let entries = parse_anilist(content).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].score, Some(8));
+        assert_eq!(entries[0].progress, Some(12));
+    }
+
+    #[test]
+    fn parse_anilist_defaults_to_the_10_point_scale() {
+        let content = r#"{"lists":[{"entries":[{"mediaId":1,"status":"COMPLETED","score":7}]}]}"#;
+        let entries = parse_anilist(content).unwrap();
+        assert_eq!(entries[0].score, Some(7));
+    }
+}
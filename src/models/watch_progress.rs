@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use sqlx::mysql::MySqlRow;
+use sqlx::{Error, FromRow, Row};
+
+use crate::arkalis_service;
+use crate::models::episode::Episode;
+use crate::models::user::User;
+
+pub struct WatchProgress {
+    pub user_id: String,
+    pub episode_id: String,
+    pub position_seconds: u32,
+    pub completed: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WatchProgress {
+    pub fn new(user: &User, episode: &Episode, position_seconds: u32, completed: bool) -> Self {
+        Self {
+            user_id: user.id.clone(),
+            episode_id: episode.id.clone(),
+            position_seconds,
+            completed,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+impl FromRow<'_, MySqlRow> for WatchProgress {
+    fn from_row(row: &'_ MySqlRow) -> Result<Self, Error> {
+        let progress = Self {
+            user_id: row.try_get("user_id")?,
+            episode_id: row.try_get("episode_id")?,
+            position_seconds: row.try_get("position_seconds")?,
+            completed: row.try_get("completed")?,
+            updated_at: row.try_get("updated_at")?,
+        };
+
+        Ok(progress)
+    }
+}
+
+impl From<WatchProgress> for arkalis_service::WatchProgress {
+    fn from(value: WatchProgress) -> Self {
+        Self {
+            episode_id: value.episode_id,
+            position_seconds: value.position_seconds,
+            completed: value.completed,
+            updated_at: value.updated_at.timestamp(),
+        }
+    }
+}
+
+pub struct ContinueWatchingEntry {
+    pub anime_id: u32,
+    pub season_id: u32,
+    pub source_id: u32,
+    pub progress: WatchProgress,
+}
+
+impl FromRow<'_, MySqlRow> for ContinueWatchingEntry {
+    fn from_row(row: &'_ MySqlRow) -> Result<Self, Error> {
+        let entry = Self {
+            anime_id: row.try_get("anime_id")?,
+            season_id: row.try_get("season_id")?,
+            source_id: row.try_get("source_id")?,
+            progress: WatchProgress::from_row(row)?,
+        };
+
+        Ok(entry)
+    }
+}
+
+impl From<ContinueWatchingEntry> for arkalis_service::ContinueWatchingEntry {
+    fn from(value: ContinueWatchingEntry) -> Self {
+        Self {
+            anime_id: value.anime_id,
+            season_id: value.season_id,
+            source_id: value.source_id,
+            progress: Some(value.progress.into()),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use num_derive::{FromPrimitive, ToPrimitive};
+
+use crate::arkalis_service;
+
+#[derive(PartialEq, Eq, Clone, Copy, FromPrimitive, ToPrimitive)]
+#[repr(u8)]
+pub enum WatchStatus {
+    Watching,
+    Completed,
+    OnHold,
+    Dropped,
+    PlanToWatch,
+}
+
+impl WatchStatus {
+    pub fn from_mal(status: &str) -> Option<Self> {
+        match status.trim() {
+            "Watching" | "1" => Some(WatchStatus::Watching),
+            "Completed" | "2" => Some(WatchStatus::Completed),
+            "On-Hold" | "3" => Some(WatchStatus::OnHold),
+            "Dropped" | "4" => Some(WatchStatus::Dropped),
+            "Plan to Watch" | "6" => Some(WatchStatus::PlanToWatch),
+            _ => None,
+        }
+    }
+
+    pub fn from_anilist(status: &str) -> Option<Self> {
+        match status.trim() {
+            "CURRENT" | "REPEATING" => Some(WatchStatus::Watching),
+            "COMPLETED" => Some(WatchStatus::Completed),
+            "PAUSED" => Some(WatchStatus::OnHold),
+            "DROPPED" => Some(WatchStatus::Dropped),
+            "PLANNING" => Some(WatchStatus::PlanToWatch),
+            _ => None,
+        }
+    }
+}
+
+pub fn status_counts_to_grpc(
+    counts: Vec<(WatchStatus, u64)>,
+) -> Vec<arkalis_service::WatchStatusCount> {
+    counts
+        .into_iter()
+        .map(|(status, count)| arkalis_service::WatchStatusCount {
+            status: status as i32,
+            count,
+        })
+        .collect()
+}
@@ -0,0 +1,230 @@
+use chrono::DateTime;
+use num_traits::FromPrimitive;
+use sea_query::{Asterisk, Expr, Iden, MysqlQueryBuilder, Order, Query};
+use sqlx::{MySql, Row, Transaction};
+use std::fmt::Write;
+
+use crate::extensions::OptionToAppResult;
+use crate::models::anime_list_entry::AnimeListEntry;
+use crate::models::anime_list_entry_cursor::AnimeListEntryCursor;
+use crate::models::cursor::Cursor;
+use crate::models::error::ApplicationError;
+use crate::models::page;
+use crate::models::page::Page;
+use crate::models::watch_status::WatchStatus;
+use crate::repositories::{anime_rating_repository, DatabaseConnection};
+
+enum AnimeListEntryQueryTable {
+    Table,
+    UserId,
+    AnimeId,
+    Status,
+    UpdatedAt,
+    Animes,
+    Id,
+    DeletedAt,
+    IsHidden,
+}
+
+impl Iden for AnimeListEntryQueryTable {
+    fn unquoted(&self, s: &mut dyn Write) {
+        let name = match self {
+            AnimeListEntryQueryTable::Table => "user_anime_lists",
+            AnimeListEntryQueryTable::UserId => "user_id",
+            AnimeListEntryQueryTable::AnimeId => "anime_id",
+            AnimeListEntryQueryTable::Status => "status",
+            AnimeListEntryQueryTable::UpdatedAt => "updated_at",
+            AnimeListEntryQueryTable::Animes => "animes",
+            AnimeListEntryQueryTable::Id => "id",
+            AnimeListEntryQueryTable::DeletedAt => "deleted_at",
+            AnimeListEntryQueryTable::IsHidden => "is_hidden",
+        };
+
+        write!(s, "{}", name).unwrap()
+    }
+}
+
+/// Imports don't carry notes, so they leave the stored ones alone with `replace_notes` off.
+pub async fn anime_list_entry_upsert(
+    tx: &mut Transaction<'_, MySql>,
+    entry: AnimeListEntry,
+    replace_notes: bool,
+) -> Result<(), ApplicationError> {
+    let (anime_id, score) = (entry.anime_id, entry.score);
+    let previous =
+        anime_rating_repository::anime_list_score_get_for_update(tx, &entry.user_id, anime_id)
+            .await?
+            .flatten();
+
+    sqlx::query("insert into user_anime_lists (user_id, anime_id, status, score, progress, notes, created_at, updated_at) values (?, ?, ?, ?, ?, ?, ?, ?) on duplicate key update status = values(status), score = values(score), progress = values(progress), notes = if(?, values(notes), notes), updated_at = values(updated_at)")
+        .bind(entry.user_id)
+        .bind(entry.anime_id)
+        .bind(entry.status as u8)
+        .bind(entry.score)
+        .bind(entry.progress)
+        .bind(entry.notes)
+        .bind(entry.created_at)
+        .bind(entry.updated_at)
+        .bind(replace_notes)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    anime_rating_repository::anime_score_apply(tx, anime_id, previous, score).await
+}
+
+pub async fn anime_list_entry_get(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    anime_id: u32,
+) -> Result<AnimeListEntry, ApplicationError> {
+    let result =
+        sqlx::query_as("select * from user_anime_lists where user_id = ? and anime_id = ?")
+            .bind(user_id)
+            .bind(anime_id)
+            .fetch_optional(&conn.connection)
+            .await
+            .map_err(|e| ApplicationError::UnknownError(e.into()))?
+            .ok_or(ApplicationError::NotFound)?;
+
+    Ok(result)
+}
+
+pub async fn anime_list_entry_delete(
+    tx: &mut Transaction<'_, MySql>,
+    user_id: &str,
+    anime_id: u32,
+) -> Result<(), ApplicationError> {
+    let previous = anime_rating_repository::anime_list_score_get_for_update(tx, user_id, anime_id)
+        .await?
+        .ok_or(ApplicationError::NotFound)?;
+
+    sqlx::query("delete from user_anime_lists where user_id = ? and anime_id = ?")
+        .bind(user_id)
+        .bind(anime_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    anime_rating_repository::anime_score_apply(tx, anime_id, previous, None).await
+}
+
+pub async fn anime_list_entry_list(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    status: Option<WatchStatus>,
+    show_all: bool,
+    page_size: Option<u32>,
+    cursor: Option<String>,
+) -> Result<Page<AnimeListEntry>, ApplicationError> {
+    let cursor = if let Some(cursor) = cursor {
+        Some(AnimeListEntryCursor::decode(&cursor)?)
+    } else {
+        None
+    };
+    let cursor_updated_at = if let Some(cursor) = &cursor {
+        Some(DateTime::from_timestamp(cursor.updated_at, 0).ok_or(
+            ApplicationError::InvalidData(anyhow::Error::msg("cursor is invalid")),
+        )?)
+    } else {
+        None
+    };
+    let page_size = page::page_size(page_size);
+
+    let sql = Query::select()
+        .column(Asterisk)
+        .from(AnimeListEntryQueryTable::Table)
+        .and_where(Expr::col(AnimeListEntryQueryTable::UserId).eq(user_id))
+        .and_where(
+            Expr::col(AnimeListEntryQueryTable::AnimeId).in_subquery(
+                Query::select()
+                    .column(AnimeListEntryQueryTable::Id)
+                    .from(AnimeListEntryQueryTable::Animes)
+                    .and_where(Expr::col(AnimeListEntryQueryTable::DeletedAt).is_null())
+                    .conditions(
+                        !show_all,
+                        |q| {
+                            q.and_where(Expr::col(AnimeListEntryQueryTable::IsHidden).eq(false));
+                        },
+                        |_| {},
+                    )
+                    .to_owned(),
+            ),
+        )
+        .conditions(
+            status.is_some(),
+            |q| {
+                q.and_where(Expr::col(AnimeListEntryQueryTable::Status).eq(status.unwrap() as u8));
+            },
+            |_| {},
+        )
+        .conditions(
+            cursor.is_some(),
+            |q| {
+                let cursor = cursor.unwrap();
+                let updated_at = cursor_updated_at.unwrap();
+                q.and_where(
+                    Expr::col(AnimeListEntryQueryTable::UpdatedAt)
+                        .lt(updated_at)
+                        .or(Expr::col(AnimeListEntryQueryTable::UpdatedAt)
+                            .eq(updated_at)
+                            .and(Expr::col(AnimeListEntryQueryTable::AnimeId).lt(cursor.anime_id))),
+                );
+            },
+            |_| {},
+        )
+        .order_by(AnimeListEntryQueryTable::UpdatedAt, Order::Desc)
+        .order_by(AnimeListEntryQueryTable::AnimeId, Order::Desc)
+        .limit(page_size + 1)
+        .to_string(MysqlQueryBuilder);
+
+    let mut result: Vec<AnimeListEntry> = sqlx::query_as(&sql)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let next_cursor = if result.len() as u64 > page_size {
+        result.truncate(page_size as usize);
+        let last = result.last().ok_or_app_result("page is empty")?;
+        let cursor = AnimeListEntryCursor {
+            updated_at: last.updated_at.timestamp(),
+            anime_id: last.anime_id,
+        };
+        Some(cursor.encode()?)
+    } else {
+        None
+    };
+
+    Ok(Page {
+        items: result,
+        next_cursor,
+    })
+}
+
+pub async fn anime_list_entry_count_by_status(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    show_all: bool,
+) -> Result<Vec<(WatchStatus, u64)>, ApplicationError> {
+    let rows = sqlx::query("select status, count(*) as total from user_anime_lists where user_id = ? and anime_id in (select id from animes where deleted_at is null and (? or is_hidden = false)) group by status")
+        .bind(user_id)
+        .bind(show_all)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let mut result = Vec::new();
+    for row in rows {
+        let status: u8 = row
+            .try_get("status")
+            .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        let total: i64 = row
+            .try_get("total")
+            .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        if let Some(status) = WatchStatus::from_u8(status) {
+            result.push((status, total as u64));
+        }
+    }
+
+    Ok(result)
+}
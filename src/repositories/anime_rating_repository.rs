@@ -0,0 +1,109 @@
+use sqlx::{MySql, Transaction};
+
+use crate::models::anime_rating::AnimeRating;
+use crate::models::error::ApplicationError;
+use crate::repositories::DatabaseConnection;
+
+/// Scores live on `user_anime_lists`, so every write that can change one reads the
+/// previous score here first and passes both to `anime_score_apply`. The outer `None`
+/// means the anime isn't on the list.
+pub async fn anime_list_score_get_for_update(
+    tx: &mut Transaction<'_, MySql>,
+    user_id: &str,
+    anime_id: u32,
+) -> Result<Option<Option<u8>>, ApplicationError> {
+    sqlx::query_scalar(
+        "select score from user_anime_lists where user_id = ? and anime_id = ? for update",
+    )
+    .bind(user_id)
+    .bind(anime_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))
+}
+
+pub async fn anime_score_apply(
+    tx: &mut Transaction<'_, MySql>,
+    anime_id: u32,
+    previous: Option<u8>,
+    score: Option<u8>,
+) -> Result<(), ApplicationError> {
+    let count_delta = i64::from(score.is_some()) - i64::from(previous.is_some());
+    let sum_delta = score.map_or(0, i64::from) - previous.map_or(0, i64::from);
+    if count_delta == 0 && sum_delta == 0 {
+        return Ok(());
+    }
+
+    sqlx::query(
+        "update animes set score_sum = score_sum + ?, score_count = score_count + ? where id = ?",
+    )
+    .bind(sum_delta)
+    .bind(count_delta)
+    .bind(anime_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+/// Only animes already on the list can be rated, so a rating never invents a watch status.
+pub async fn anime_rating_set(
+    tx: &mut Transaction<'_, MySql>,
+    rating: &AnimeRating,
+) -> Result<(), ApplicationError> {
+    let previous = anime_list_score_get_for_update(tx, &rating.user_id, rating.anime_id)
+        .await?
+        .ok_or(ApplicationError::InvalidData(anyhow::Error::msg(
+            "add the anime to your list before rating it",
+        )))?;
+
+    sqlx::query(
+        "update user_anime_lists set score = ?, updated_at = ? where user_id = ? and anime_id = ?",
+    )
+    .bind(rating.score)
+    .bind(rating.updated_at)
+    .bind(&rating.user_id)
+    .bind(rating.anime_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    anime_score_apply(tx, rating.anime_id, previous, Some(rating.score)).await
+}
+
+pub async fn anime_rating_delete(
+    tx: &mut Transaction<'_, MySql>,
+    user_id: &str,
+    anime_id: u32,
+) -> Result<(), ApplicationError> {
+    let previous = anime_list_score_get_for_update(tx, user_id, anime_id)
+        .await?
+        .flatten()
+        .ok_or(ApplicationError::NotFound)?;
+
+    sqlx::query("update user_anime_lists set score = null where user_id = ? and anime_id = ?")
+        .bind(user_id)
+        .bind(anime_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    anime_score_apply(tx, anime_id, Some(previous), None).await
+}
+
+pub async fn anime_rating_get(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    anime_id: u32,
+) -> Result<Option<u8>, ApplicationError> {
+    let result: Option<Option<u8>> =
+        sqlx::query_scalar("select score from user_anime_lists where user_id = ? and anime_id = ?")
+            .bind(user_id)
+            .bind(anime_id)
+            .fetch_optional(&conn.connection)
+            .await
+            .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result.flatten())
+}
@@ -1,17 +1,38 @@
 use crate::arkalis_service::SearchAnimeRequest;
+use crate::extensions::OptionToAppResult;
 use crate::models::anime::Anime;
+use crate::models::anime_cursor::{AnimeCursor, AnimeCursorValue};
+use crate::models::anime_in_anime_list::AnimeInAnimeList;
+use crate::models::anime_list::AnimeList;
+use crate::models::anime_sort_key::AnimeSortKey;
+use crate::models::cursor::Cursor;
 use crate::models::error::ApplicationError;
-use crate::repositories::DatabaseConnection;
-use chrono::DateTime;
-use sea_query::{Expr, Iden, MysqlQueryBuilder, Query, SimpleExpr};
+use crate::models::genre::Genre;
+use crate::models::page;
+use crate::models::page::Page;
+use crate::models::search_text;
+use crate::repositories::{
+    map_unique_violation, DatabaseConnection, EPISODE_SEQUENCE_IN_USE, SEASON_SEQUENCE_IN_USE,
+};
+use async_stream::try_stream;
+use chrono::{DateTime, Utc};
+use num_traits::{FromPrimitive, ToPrimitive};
+use sea_query::{Alias, Expr, Iden, MysqlQueryBuilder, Order, Query, SimpleExpr, Value};
+use sqlx::{MySql, Row, Transaction};
 use std::fmt::Write;
+use tokio_stream::{Stream, StreamExt};
 
+const LIST_ENTRY_LOOKUP_CHUNK_SIZE: usize = 1000;
+
+#[derive(Clone)]
 enum AnimeQueryTable {
     Table,
     Id,
     Titles,
     TitleSearch,
+    MainTitle,
     Synopsis,
+    SynopsisSearch,
     ThumbnailId,
     BannerId,
     IsHidden,
@@ -21,6 +42,9 @@ enum AnimeQueryTable {
     Genre,
     ReleaseDate,
     AnimeInLists,
+    ScoreCount,
+    AverageScore,
+    DeletedAt,
 }
 
 impl Iden for AnimeQueryTable {
@@ -31,7 +55,9 @@ impl Iden for AnimeQueryTable {
             match self {
                 AnimeQueryTable::Table => "animes",
                 AnimeQueryTable::TitleSearch => "title_search",
+                AnimeQueryTable::MainTitle => "main_title",
                 AnimeQueryTable::Synopsis => "synopsis",
+                AnimeQueryTable::SynopsisSearch => "synopsis_search",
                 AnimeQueryTable::IsNsfw => "is_nsfw",
                 AnimeQueryTable::Genre => "genre",
                 AnimeQueryTable::ReleaseDate => "release_date",
@@ -42,6 +68,9 @@ impl Iden for AnimeQueryTable {
                 AnimeQueryTable::CreatedBy => "created_by",
                 AnimeQueryTable::CreatedAt => "created_at",
                 AnimeQueryTable::AnimeInLists => "anime_in_lists",
+                AnimeQueryTable::ScoreCount => "score_count",
+                AnimeQueryTable::AverageScore => "average_score",
+                AnimeQueryTable::DeletedAt => "deleted_at",
                 AnimeQueryTable::Id => "id",
             }
         )
@@ -49,13 +78,26 @@ impl Iden for AnimeQueryTable {
     }
 }
 
-pub async fn anime_add(conn: &DatabaseConnection, anime: Anime) -> Result<u32, ApplicationError> {
+struct SearchQuery {
+    count_query: String,
+    query: String,
+    sort_key: AnimeSortKey,
+    descending: bool,
+    page_size: u64,
+}
+
+pub async fn anime_add(
+    tx: &mut Transaction<'_, MySql>,
+    anime: Anime,
+) -> Result<u32, ApplicationError> {
     let anime_in_list = anime.get_anime_in_anime_list_json()?;
 
-    let id = sqlx::query("insert into animes (titles, title_search, synopsis, thumbnail_id, banner_id, is_hidden, is_nsfw, created_by, created_at, genre, release_date, anime_in_lists) values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)")
+    let id = sqlx::query("insert into animes (titles, title_search, main_title, synopsis, synopsis_search, thumbnail_id, banner_id, is_hidden, is_nsfw, created_by, created_at, genre, release_date, anime_in_lists) values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)")
         .bind(anime.get_titles_json()?)
         .bind(anime.title_search)
+        .bind(anime.main_title)
         .bind(anime.synopsis)
+        .bind(anime.synopsis_search)
         .bind(anime.thumbnail_id)
         .bind(anime.banner_id)
         .bind(anime.is_hidden)
@@ -65,12 +107,103 @@ pub async fn anime_add(conn: &DatabaseConnection, anime: Anime) -> Result<u32, A
         .bind(anime.genre.bits())
         .bind(anime.release_date)
         .bind(anime_in_list)
-        .execute(&conn.connection)
+        .execute(&mut **tx)
         .await
         .map_err(|e| ApplicationError::UnknownError(e.into()))?
-        .last_insert_id();
+        .last_insert_id() as u32;
+
+    anime_external_ids_replace(tx, id, &anime.anime_in_lists).await?;
+
+    Ok(id)
+}
+
+async fn anime_external_ids_replace(
+    tx: &mut Transaction<'_, MySql>,
+    id: u32,
+    anime_in_lists: &[AnimeInAnimeList],
+) -> Result<(), ApplicationError> {
+    sqlx::query("delete from anime_external_ids where anime_id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let mut inserted: Vec<&AnimeInAnimeList> = Vec::new();
+    for entry in anime_in_lists {
+        if inserted.contains(&entry) {
+            continue;
+        }
+
+        let result = sqlx::query(
+            "insert into anime_external_ids (anime_list, id_in_list, anime_id) values (?, ?, ?)",
+        )
+        .bind(entry.anime_list().to_u8())
+        .bind(entry.id_in_list())
+        .bind(id)
+        .execute(&mut **tx)
+        .await;
+        match result {
+            Ok(_) => inserted.push(entry),
+            Err(sqlx::Error::Database(db)) if db.is_unique_violation() => {
+                return Err(external_id_conflict(tx, entry).await)
+            }
+            Err(e) => return Err(ApplicationError::UnknownError(e.into())),
+        }
+    }
+
+    Ok(())
+}
+
+async fn external_id_conflict(
+    tx: &mut Transaction<'_, MySql>,
+    entry: &AnimeInAnimeList,
+) -> ApplicationError {
+    let owner = sqlx::query("select animes.id, animes.deleted_at from anime_external_ids inner join animes on animes.id = anime_external_ids.anime_id where anime_external_ids.anime_list = ? and anime_external_ids.id_in_list = ?")
+        .bind(entry.anime_list().to_u8())
+        .bind(entry.id_in_list())
+        .fetch_one(&mut **tx)
+        .await
+        .and_then(|row| {
+            let id: u32 = row.try_get("id")?;
+            let deleted_at: Option<DateTime<Utc>> = row.try_get("deleted_at")?;
+            Ok((id, deleted_at))
+        });
+
+    let message = match owner {
+        Ok((id, Some(_))) => format!(
+            "id {} is linked to anime {} in the trash, restore it instead",
+            entry.id_in_list(),
+            id
+        ),
+        Ok((id, None)) => format!(
+            "id {} is already linked to anime {}",
+            entry.id_in_list(),
+            id
+        ),
+        Err(e) => return ApplicationError::UnknownError(e.into()),
+    };
+
+    ApplicationError::InvalidData(anyhow::Error::msg(message))
+}
+
+pub async fn anime_get_external_id_conflicts(
+    conn: &DatabaseConnection,
+) -> Result<Vec<(u32, String, u32)>, ApplicationError> {
+    let rows = sqlx::query("select animes.id, entries.id_in_list, anime_external_ids.anime_id as linked_anime_id from animes cross join json_table(animes.anime_in_lists, '$[*]' columns (anime_list varchar(20) path '$.anime_list', id_in_list varchar(100) path '$.id_in_list')) as entries inner join anime_external_ids on anime_external_ids.anime_list = case entries.anime_list when 'MyAnimeList' then 0 when 'AniList' then 1 end and anime_external_ids.id_in_list = entries.id_in_list where anime_external_ids.anime_id <> animes.id")
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
 
-    Ok(id as u32)
+    rows.iter()
+        .map(|row| {
+            Ok((
+                row.try_get("id")?,
+                row.try_get("id_in_list")?,
+                row.try_get("linked_anime_id")?,
+            ))
+        })
+        .collect::<Result<Vec<_>, sqlx::Error>>()
+        .map_err(|e| ApplicationError::UnknownError(e.into()))
 }
 
 pub async fn anime_get_by_id(
@@ -82,6 +215,7 @@ pub async fn anime_get_by_id(
         .columns(get_columns())
         .from(AnimeQueryTable::Table)
         .and_where(Expr::col(AnimeQueryTable::Id).eq(id))
+        .and_where(Expr::col(AnimeQueryTable::DeletedAt).is_null())
         .conditions(
             !show_all,
             |q| {
@@ -100,11 +234,136 @@ pub async fn anime_get_by_id(
     Ok(result)
 }
 
+pub async fn anime_get_ids_by_list_entry(
+    conn: &DatabaseConnection,
+    list_entry: &AnimeInAnimeList,
+) -> Result<Vec<u32>, ApplicationError> {
+    let result = sqlx::query(
+        "select anime_external_ids.anime_id from anime_external_ids inner join animes on animes.id = anime_external_ids.anime_id where anime_external_ids.anime_list = ? and anime_external_ids.id_in_list = ? and animes.deleted_at is null",
+    )
+    .bind(list_entry.anime_list().to_u8())
+    .bind(list_entry.id_in_list())
+    .fetch_all(&conn.connection)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?
+    .iter()
+    .map(|row| row.try_get("anime_id"))
+    .collect::<Result<Vec<u32>, _>>()
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result)
+}
+
+pub async fn anime_get_ids_by_list_entries(
+    conn: &DatabaseConnection,
+    anime_list: AnimeList,
+    ids_in_list: &[String],
+    show_all: bool,
+) -> Result<Vec<(String, u32)>, ApplicationError> {
+    let mut result = Vec::new();
+    for chunk in ids_in_list.chunks(LIST_ENTRY_LOOKUP_CHUNK_SIZE) {
+        let sql = format!(
+            "select anime_external_ids.id_in_list, anime_external_ids.anime_id from anime_external_ids inner join animes on animes.id = anime_external_ids.anime_id where anime_external_ids.anime_list = ? and anime_external_ids.id_in_list in ({}) and animes.deleted_at is null and (? or animes.is_hidden = false)",
+            vec!["?"; chunk.len()].join(", ")
+        );
+        let mut query = sqlx::query(&sql).bind(anime_list.to_u8());
+        for id_in_list in chunk {
+            query = query.bind(id_in_list);
+        }
+        query = query.bind(show_all);
+
+        let rows = query
+            .fetch_all(&conn.connection)
+            .await
+            .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        for row in rows {
+            let id_in_list: String = row
+                .try_get("id_in_list")
+                .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+            let anime_id: u32 = row
+                .try_get("anime_id")
+                .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+            result.push((id_in_list, anime_id));
+        }
+    }
+
+    Ok(result)
+}
+
+pub async fn anime_get_by_external_id(
+    conn: &DatabaseConnection,
+    list_entry: &AnimeInAnimeList,
+    show_all: bool,
+) -> Result<Anime, ApplicationError> {
+    let id = anime_get_ids_by_list_entry(conn, list_entry)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(ApplicationError::NotFound)?;
+
+    anime_get_by_id(conn, id, show_all).await
+}
+
 pub async fn anime_search(
     conn: &DatabaseConnection,
     filters: SearchAnimeRequest,
     show_all: bool,
-) -> Result<Vec<Anime>, ApplicationError> {
+) -> Result<(Page<Anime>, u64), ApplicationError> {
+    let SearchQuery {
+        count_query,
+        query,
+        sort_key,
+        descending,
+        page_size,
+    } = build_search_query(filters, show_all, true)?;
+
+    let total: i64 = sqlx::query_scalar(&count_query)
+        .fetch_one(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let mut result: Vec<Anime> = sqlx::query_as(&query)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let next_cursor = if result.len() as u64 > page_size {
+        result.truncate(page_size as usize);
+        let last = result.last().ok_or_app_result("page is empty")?;
+        Some(AnimeCursor::from_anime(last, sort_key, descending)?.encode()?)
+    } else {
+        None
+    };
+
+    let page = Page {
+        items: result,
+        next_cursor,
+    };
+
+    Ok((page, total as u64))
+}
+
+pub fn anime_search_stream(
+    conn: &DatabaseConnection,
+    filters: SearchAnimeRequest,
+    show_all: bool,
+) -> Result<impl Stream<Item = Result<Anime, ApplicationError>>, ApplicationError> {
+    let SearchQuery { query, .. } = build_search_query(filters, show_all, false)?;
+    let pool = conn.connection.clone();
+
+    Ok(try_stream! {
+        let mut rows = sqlx::query_as::<_, Anime>(&query).fetch(&pool);
+        while let Some(anime) = rows.next().await {
+            yield anime.map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        }
+    })
+}
+
+fn build_search_query(
+    filters: SearchAnimeRequest,
+    show_all: bool,
+    paginated: bool,
+) -> Result<SearchQuery, ApplicationError> {
     let SearchAnimeRequest {
         title,
         synopsis,
@@ -112,6 +371,16 @@ pub async fn anime_search(
         genre,
         start_release_date,
         end_release_date,
+        page_size,
+        cursor,
+        sort_by,
+        descending,
+        query,
+        genre_all,
+        genre_any,
+        genre_exclude,
+        min_score,
+        min_votes,
     } = filters;
 
     let start_release_date = if let Some(dt) = start_release_date {
@@ -134,34 +403,112 @@ pub async fn anime_search(
         None
     };
 
-    let query = Query::select()
-        .columns(get_columns())
+    let genre_all = match (
+        parse_genre_mask(genre, "genre")?,
+        parse_genre_mask(genre_all, "genre_all")?,
+    ) {
+        (Some(genre), Some(genre_all)) => Some(genre | genre_all),
+        (genre, genre_all) => genre.or(genre_all),
+    };
+    let genre_any = parse_genre_mask(genre_any, "genre_any")?;
+    let genre_exclude = parse_genre_mask(genre_exclude, "genre_exclude")?;
+
+    let text_searches = [
+        title.map(|title| full_text_search(&[AnimeQueryTable::TitleSearch], &title)),
+        synopsis.map(|synopsis| full_text_search(&[AnimeQueryTable::SynopsisSearch], &synopsis)),
+        query.map(|query| {
+            full_text_search(
+                &[
+                    AnimeQueryTable::TitleSearch,
+                    AnimeQueryTable::SynopsisSearch,
+                ],
+                &query,
+            )
+        }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+
+    let relevance = text_searches
+        .iter()
+        .filter_map(|(_, score)| score.clone())
+        .reduce(|acc, score| acc.add(score));
+
+    let sort_key = match sort_by {
+        Some(sort_by) => AnimeSortKey::from_i32(sort_by).ok_or(ApplicationError::InvalidData(
+            anyhow::Error::msg("sort_by is not a valid sort key"),
+        ))?,
+        None if relevance.is_some() => AnimeSortKey::Relevance,
+        None => AnimeSortKey::Id,
+    };
+
+    if sort_key == AnimeSortKey::Relevance && relevance.is_none() {
+        return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+            "sorting by relevance requires a title, synopsis or query filter",
+        )));
+    }
+
+    let descending = descending || sort_key == AnimeSortKey::Relevance;
+
+    let cursor = if let Some(cursor) = cursor {
+        Some(AnimeCursor::parse(&cursor, sort_key, descending)?)
+    } else {
+        None
+    };
+
+    let page_size = page::page_size(page_size);
+
+    let mut base_query = Query::select();
+    base_query
         .from(AnimeQueryTable::Table)
+        .and_where(Expr::col(AnimeQueryTable::DeletedAt).is_null());
+
+    for (condition, _) in text_searches {
+        base_query.and_where(condition);
+    }
+
+    base_query
         .conditions(
-            title.is_some(),
+            is_nsfw.is_some(),
             move |q| {
-                q.and_where(complete_like(AnimeQueryTable::TitleSearch, title.unwrap()));
+                q.and_where(Expr::col(AnimeQueryTable::IsNsfw).eq(is_nsfw.unwrap()));
             },
             |_| {},
         )
         .conditions(
-            synopsis.is_some(),
-            move |q| {
-                q.and_where(complete_like(AnimeQueryTable::Synopsis, synopsis.unwrap()));
+            genre_all.is_some(),
+            |q| {
+                let mask = genre_all.unwrap();
+                q.and_where(Expr::expr(masked_genre(mask)).eq(mask));
             },
             |_| {},
         )
         .conditions(
-            is_nsfw.is_some(),
-            move |q| {
-                q.and_where(Expr::col(AnimeQueryTable::IsNsfw).eq(is_nsfw.unwrap()));
+            genre_any.is_some(),
+            |q| {
+                q.and_where(Expr::expr(masked_genre(genre_any.unwrap())).ne(0));
             },
             |_| {},
         )
         .conditions(
-            genre.is_some(),
+            genre_exclude.is_some(),
             |q| {
-                q.and_where(Expr::col(AnimeQueryTable::Genre).eq(genre.unwrap()));
+                q.and_where(Expr::expr(masked_genre(genre_exclude.unwrap())).eq(0));
+            },
+            |_| {},
+        )
+        .conditions(
+            min_score.is_some(),
+            move |q| {
+                q.and_where(Expr::col(AnimeQueryTable::AverageScore).gte(min_score.unwrap()));
+            },
+            |_| {},
+        )
+        .conditions(
+            min_votes.is_some(),
+            move |q| {
+                q.and_where(Expr::col(AnimeQueryTable::ScoreCount).gte(min_votes.unwrap()));
             },
             |_| {},
         )
@@ -187,25 +534,74 @@ pub async fn anime_search(
                 q.and_where(Expr::col(AnimeQueryTable::IsHidden).eq(false));
             },
             |_| {},
-        )
+        );
+
+    let count_query = base_query
+        .clone()
+        .expr(Expr::col(AnimeQueryTable::Id).count())
         .to_string(MysqlQueryBuilder);
 
-    let result = sqlx::query_as(&query)
-        .fetch_all(&conn.connection)
-        .await
-        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+    let order = if descending { Order::Desc } else { Order::Asc };
+    let sort_expr = sort_expr(sort_key, &relevance);
+    let keyset = cursor
+        .map(|cursor| keyset_condition(cursor, sort_expr.clone()))
+        .transpose()?;
 
-    Ok(result)
+    let query = base_query
+        .columns(get_columns())
+        .conditions(
+            relevance.is_some(),
+            |q| {
+                q.expr_as(relevance.clone().unwrap(), Alias::new("relevance"));
+            },
+            |_| {},
+        )
+        .conditions(
+            keyset.is_some(),
+            |q| {
+                q.and_where(keyset.unwrap());
+            },
+            |_| {},
+        )
+        .order_by_expr(sort_expr, order.clone())
+        .conditions(
+            sort_key != AnimeSortKey::Id,
+            |q| {
+                q.order_by(AnimeQueryTable::Id, order);
+            },
+            |_| {},
+        )
+        .conditions(
+            paginated,
+            |q| {
+                q.limit(page_size + 1);
+            },
+            |_| {},
+        )
+        .to_string(MysqlQueryBuilder);
+
+    Ok(SearchQuery {
+        count_query,
+        query,
+        sort_key,
+        descending,
+        page_size,
+    })
 }
 
-pub async fn anime_update(conn: &DatabaseConnection, anime: Anime) -> Result<(), ApplicationError> {
+pub async fn anime_update(
+    tx: &mut Transaction<'_, MySql>,
+    anime: Anime,
+) -> Result<(), ApplicationError> {
     let titles = anime.get_titles_json()?;
     let anime_in_list = anime.get_anime_in_anime_list_json()?;
 
-    sqlx::query("update animes set titles = ?, title_search = ?, synopsis = ?, thumbnail_id = ?, banner_id = ?, genre = ?, release_date = ?, anime_in_lists = ?, is_hidden = ? where id = ?")
+    sqlx::query("update animes set titles = ?, title_search = ?, main_title = ?, synopsis = ?, synopsis_search = ?, thumbnail_id = ?, banner_id = ?, genre = ?, release_date = ?, anime_in_lists = ?, is_hidden = ? where id = ? and deleted_at is null")
         .bind(titles)
         .bind(anime.title_search)
+        .bind(anime.main_title)
         .bind(anime.synopsis)
+        .bind(anime.synopsis_search)
         .bind(anime.thumbnail_id)
         .bind(anime.banner_id)
         .bind(anime.genre.bits())
@@ -213,6 +609,134 @@ pub async fn anime_update(conn: &DatabaseConnection, anime: Anime) -> Result<(),
         .bind(anime_in_list)
         .bind(anime.is_hidden)
         .bind(anime.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    anime_external_ids_replace(tx, anime.id.unwrap_or(0), &anime.anime_in_lists).await?;
+
+    Ok(())
+}
+
+pub async fn anime_delete(
+    tx: &mut Transaction<'_, MySql>,
+    anime: Anime,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update animes set deleted_at = ? where id = ? and deleted_at is null")
+        .bind(anime.deleted_at)
+        .bind(anime.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    sqlx::query("update seasons set deleted_at = ? where anime_id = ? and deleted_at is null")
+        .bind(anime.deleted_at)
+        .bind(anime.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    sqlx::query("update episodes set deleted_at = ? where season_id in (select id from seasons where anime_id = ?) and deleted_at is null")
+        .bind(anime.deleted_at)
+        .bind(anime.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn anime_restore(
+    tx: &mut Transaction<'_, MySql>,
+    id: u32,
+) -> Result<(), ApplicationError> {
+    let deleted_at: DateTime<Utc> =
+        sqlx::query_scalar("select deleted_at from animes where id = ? and deleted_at is not null")
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| ApplicationError::UnknownError(e.into()))?
+            .ok_or(ApplicationError::NotFound)?;
+
+    sqlx::query("update episodes set deleted_at = null where season_id in (select id from seasons where anime_id = ?) and deleted_at = ?")
+        .bind(id)
+        .bind(deleted_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(map_unique_violation(EPISODE_SEQUENCE_IN_USE))?;
+
+    sqlx::query("update seasons set deleted_at = null where anime_id = ? and deleted_at = ?")
+        .bind(id)
+        .bind(deleted_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(map_unique_violation(SEASON_SEQUENCE_IN_USE))?;
+
+    sqlx::query("update animes set deleted_at = null where id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn anime_purge(tx: &mut Transaction<'_, MySql>, id: u32) -> Result<(), ApplicationError> {
+    sqlx::query("select id from animes where id = ? and deleted_at is not null for update")
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    sqlx::query(
+        "delete from episodes where season_id in (select id from seasons where anime_id = ?)",
+    )
+    .bind(id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    sqlx::query("delete from seasons where anime_id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    sqlx::query("delete from animes where id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn anime_get_not_indexed(
+    conn: &DatabaseConnection,
+) -> Result<Vec<Anime>, ApplicationError> {
+    let query = Query::select()
+        .columns(get_columns())
+        .from(AnimeQueryTable::Table)
+        .and_where(Expr::col(AnimeQueryTable::SynopsisSearch).is_null())
+        .to_string(MysqlQueryBuilder);
+
+    let result = sqlx::query_as(&query)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result)
+}
+
+pub async fn anime_update_search(
+    conn: &DatabaseConnection,
+    anime: Anime,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update animes set title_search = ?, synopsis_search = ? where id = ?")
+        .bind(anime.title_search)
+        .bind(anime.synopsis_search)
+        .bind(anime.id)
         .execute(&conn.connection)
         .await
         .map_err(|e| ApplicationError::UnknownError(e.into()))?;
@@ -224,12 +748,122 @@ fn complete_like(col: AnimeQueryTable, value: String) -> SimpleExpr {
     Expr::col(col).like(format!("%{}%", value))
 }
 
-fn get_columns() -> [AnimeQueryTable; 13] {
+fn full_text_search(columns: &[AnimeQueryTable], value: &str) -> (SimpleExpr, Option<SimpleExpr>) {
+    if let Some(boolean_query) = search_text::to_boolean_query(value) {
+        let columns = columns
+            .iter()
+            .map(|col| col.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let score = Expr::cust_with_values(
+            format!("match ({}) against (? in boolean mode)", columns),
+            [boolean_query],
+        );
+        return (score.clone(), Some(score));
+    }
+
+    let value = search_text::normalize(value);
+    let condition = columns
+        .iter()
+        .map(|col| complete_like(col.clone(), value.clone()))
+        .reduce(|acc, like| acc.or(like))
+        .unwrap_or(Expr::val(true).into());
+
+    (condition, None)
+}
+
+fn parse_genre_mask(
+    mask: Option<u64>,
+    field: &'static str,
+) -> Result<Option<u64>, ApplicationError> {
+    let Some(mask) = mask.filter(|mask| *mask != 0) else {
+        return Ok(None);
+    };
+
+    let genre = Genre::from_bits(mask).ok_or(ApplicationError::InvalidData(anyhow::Error::msg(
+        format!("{} is not a valid genre flag", field),
+    )))?;
+
+    Ok(Some(genre.bits()))
+}
+
+fn masked_genre(mask: u64) -> SimpleExpr {
+    Expr::cust_with_values(
+        format!("({} & ?)", AnimeQueryTable::Genre.to_string()),
+        [mask],
+    )
+}
+
+fn sort_expr(sort_key: AnimeSortKey, relevance: &Option<SimpleExpr>) -> SimpleExpr {
+    match sort_key {
+        AnimeSortKey::Id => Expr::col(AnimeQueryTable::Id).into(),
+        AnimeSortKey::ReleaseDate => Expr::col(AnimeQueryTable::ReleaseDate).into(),
+        AnimeSortKey::CreatedAt => Expr::col(AnimeQueryTable::CreatedAt).into(),
+        AnimeSortKey::MainTitle => Expr::col(AnimeQueryTable::MainTitle).into(),
+        AnimeSortKey::Score => Expr::col(AnimeQueryTable::AverageScore).into(),
+        AnimeSortKey::Relevance => relevance
+            .clone()
+            .unwrap_or(Expr::col(AnimeQueryTable::Id).into()),
+    }
+}
+
+fn keyset_condition(
+    cursor: AnimeCursor,
+    sort_expr: SimpleExpr,
+) -> Result<SimpleExpr, ApplicationError> {
+    let invalid_cursor =
+        || ApplicationError::InvalidData(anyhow::Error::msg("cursor value is invalid"));
+    let value: Value = match (cursor.sort_key, cursor.value) {
+        (AnimeSortKey::Id, AnimeCursorValue::Integer(_)) => {
+            return Ok(if cursor.descending {
+                Expr::col(AnimeQueryTable::Id).lt(cursor.id)
+            } else {
+                Expr::col(AnimeQueryTable::Id).gt(cursor.id)
+            });
+        }
+        (AnimeSortKey::ReleaseDate, AnimeCursorValue::Integer(timestamp)) => {
+            DateTime::from_timestamp(timestamp, 0)
+                .ok_or_else(invalid_cursor)?
+                .date_naive()
+                .into()
+        }
+        (AnimeSortKey::CreatedAt, AnimeCursorValue::Integer(timestamp)) => {
+            DateTime::from_timestamp(timestamp, 0)
+                .ok_or_else(invalid_cursor)?
+                .into()
+        }
+        (AnimeSortKey::MainTitle, AnimeCursorValue::Text(value)) => value.into(),
+        (AnimeSortKey::Relevance | AnimeSortKey::Score, AnimeCursorValue::Float(value)) => {
+            value.into()
+        }
+        _ => return Err(invalid_cursor()),
+    };
+
+    let condition = if cursor.descending {
+        Expr::expr(sort_expr.clone())
+            .lt(value.clone())
+            .or(Expr::expr(sort_expr)
+                .eq(value)
+                .and(Expr::col(AnimeQueryTable::Id).lt(cursor.id)))
+    } else {
+        Expr::expr(sort_expr.clone())
+            .gt(value.clone())
+            .or(Expr::expr(sort_expr)
+                .eq(value)
+                .and(Expr::col(AnimeQueryTable::Id).gt(cursor.id)))
+    };
+
+    Ok(condition)
+}
+
+fn get_columns() -> [AnimeQueryTable; 18] {
     [
         AnimeQueryTable::Id,
         AnimeQueryTable::Titles,
         AnimeQueryTable::TitleSearch,
+        AnimeQueryTable::MainTitle,
         AnimeQueryTable::Synopsis,
+        AnimeQueryTable::SynopsisSearch,
         AnimeQueryTable::ThumbnailId,
         AnimeQueryTable::BannerId,
         AnimeQueryTable::IsHidden,
@@ -239,5 +873,8 @@ fn get_columns() -> [AnimeQueryTable; 13] {
         AnimeQueryTable::Genre,
         AnimeQueryTable::ReleaseDate,
         AnimeQueryTable::AnimeInLists,
+        AnimeQueryTable::ScoreCount,
+        AnimeQueryTable::AverageScore,
+        AnimeQueryTable::DeletedAt,
     ]
 }
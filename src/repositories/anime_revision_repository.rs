@@ -0,0 +1,82 @@
+use sqlx::types::Json;
+use sqlx::{MySql, Row, Transaction};
+
+use crate::models::anime_revision::AnimeRevision;
+use crate::models::error::ApplicationError;
+use crate::repositories::DatabaseConnection;
+
+pub async fn anime_revision_add(
+    tx: &mut Transaction<'_, MySql>,
+    revision: AnimeRevision,
+) -> Result<u32, ApplicationError> {
+    sqlx::query("select id from animes where id = ? for update")
+        .bind(revision.anime_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    let number: u32 = sqlx::query(
+        "select cast(coalesce(max(revision), 0) + 1 as unsigned) as revision from anime_revisions where anime_id = ?",
+    )
+    .bind(revision.anime_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?
+    .try_get::<u64, _>("revision")
+    .map_err(|e| ApplicationError::UnknownError(e.into()))? as u32;
+
+    sqlx::query("insert into anime_revisions (anime_id, revision, snapshot, created_by, created_at, reverted_from) values (?, ?, ?, ?, ?, ?)")
+        .bind(revision.anime_id)
+        .bind(number)
+        .bind(Json(revision.snapshot))
+        .bind(revision.created_by)
+        .bind(revision.created_at)
+        .bind(revision.reverted_from)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(number)
+}
+
+pub async fn anime_revision_exists(
+    tx: &mut Transaction<'_, MySql>,
+    anime_id: u32,
+) -> Result<bool, ApplicationError> {
+    let result = sqlx::query("select id from anime_revisions where anime_id = ? limit 1")
+        .bind(anime_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result.is_some())
+}
+
+pub async fn anime_revision_get_by_anime(
+    conn: &DatabaseConnection,
+    anime_id: u32,
+) -> Result<Vec<AnimeRevision>, ApplicationError> {
+    let result =
+        sqlx::query_as("select * from anime_revisions where anime_id = ? order by revision desc")
+            .bind(anime_id)
+            .fetch_all(&conn.connection)
+            .await
+            .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result)
+}
+
+pub async fn anime_revision_get(
+    conn: &DatabaseConnection,
+    anime_id: u32,
+    revision: u32,
+) -> Result<AnimeRevision, ApplicationError> {
+    sqlx::query_as("select * from anime_revisions where anime_id = ? and revision = ?")
+        .bind(anime_id)
+        .bind(revision)
+        .fetch_optional(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)
+}
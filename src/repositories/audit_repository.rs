@@ -0,0 +1,197 @@
+use chrono::DateTime;
+use num_traits::{FromPrimitive, ToPrimitive};
+use sea_query::{Expr, Iden, MysqlQueryBuilder, Order, Query};
+use sqlx::types::Json;
+use sqlx::{MySql, Transaction};
+use std::fmt::Write;
+
+use crate::arkalis_service::ListAuditEventsRequest;
+use crate::extensions::OptionToAppResult;
+use crate::models::audit_event::AuditEvent;
+use crate::models::audit_event_cursor::AuditEventCursor;
+use crate::models::cursor::Cursor;
+use crate::models::entity_type::EntityType;
+use crate::models::error::ApplicationError;
+use crate::models::page;
+use crate::models::page::Page;
+use crate::repositories::DatabaseConnection;
+
+enum AuditEventQueryTable {
+    Table,
+    Id,
+    UserId,
+    RpcName,
+    EntityType,
+    EntityId,
+    Diff,
+    CreatedAt,
+}
+
+impl Iden for AuditEventQueryTable {
+    fn unquoted(&self, s: &mut dyn Write) {
+        let name = match self {
+            AuditEventQueryTable::Table => "audit_events",
+            AuditEventQueryTable::Id => "id",
+            AuditEventQueryTable::UserId => "user_id",
+            AuditEventQueryTable::RpcName => "rpc_name",
+            AuditEventQueryTable::EntityType => "entity_type",
+            AuditEventQueryTable::EntityId => "entity_id",
+            AuditEventQueryTable::Diff => "diff",
+            AuditEventQueryTable::CreatedAt => "created_at",
+        };
+
+        write!(s, "{}", name).unwrap()
+    }
+}
+
+pub async fn audit_event_add(
+    tx: &mut Transaction<'_, MySql>,
+    event: AuditEvent,
+) -> Result<(), ApplicationError> {
+    sqlx::query("insert into audit_events (user_id, rpc_name, entity_type, entity_id, diff, created_at) values (?, ?, ?, ?, ?, ?)")
+        .bind(event.user_id)
+        .bind(event.rpc_name)
+        .bind(event.entity_type as u8)
+        .bind(event.entity_id)
+        .bind(Json(event.diff))
+        .bind(event.created_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn audit_event_list(
+    conn: &DatabaseConnection,
+    filters: ListAuditEventsRequest,
+) -> Result<Page<AuditEvent>, ApplicationError> {
+    let ListAuditEventsRequest {
+        user_id,
+        entity_type,
+        entity_id,
+        start_time,
+        end_time,
+        page_size,
+        cursor,
+    } = filters;
+
+    let entity_type = if let Some(entity_type) = entity_type {
+        Some(
+            EntityType::from_i32(entity_type)
+                .ok_or(ApplicationError::InvalidData(anyhow::Error::msg(
+                    "entity_type is invalid",
+                )))?
+                .to_u8()
+                .unwrap_or(0),
+        )
+    } else {
+        None
+    };
+
+    let start_time = if let Some(dt) = start_time {
+        Some(
+            DateTime::from_timestamp(dt, 0).ok_or(ApplicationError::InvalidData(
+                anyhow::Error::msg("start_time is invalid unix time"),
+            ))?,
+        )
+    } else {
+        None
+    };
+
+    let end_time = if let Some(dt) = end_time {
+        Some(
+            DateTime::from_timestamp(dt, 0).ok_or(ApplicationError::InvalidData(
+                anyhow::Error::msg("end_time is invalid unix time"),
+            ))?,
+        )
+    } else {
+        None
+    };
+
+    let cursor = if let Some(cursor) = cursor {
+        Some(AuditEventCursor::decode(&cursor)?)
+    } else {
+        None
+    };
+
+    let page_size = page::page_size(page_size);
+
+    let query = Query::select()
+        .columns([
+            AuditEventQueryTable::Id,
+            AuditEventQueryTable::UserId,
+            AuditEventQueryTable::RpcName,
+            AuditEventQueryTable::EntityType,
+            AuditEventQueryTable::EntityId,
+            AuditEventQueryTable::Diff,
+            AuditEventQueryTable::CreatedAt,
+        ])
+        .from(AuditEventQueryTable::Table)
+        .conditions(
+            user_id.is_some(),
+            |q| {
+                q.and_where(Expr::col(AuditEventQueryTable::UserId).eq(user_id.unwrap()));
+            },
+            |_| {},
+        )
+        .conditions(
+            entity_type.is_some(),
+            |q| {
+                q.and_where(Expr::col(AuditEventQueryTable::EntityType).eq(entity_type.unwrap()));
+            },
+            |_| {},
+        )
+        .conditions(
+            entity_id.is_some(),
+            |q| {
+                q.and_where(Expr::col(AuditEventQueryTable::EntityId).eq(entity_id.unwrap()));
+            },
+            |_| {},
+        )
+        .conditions(
+            start_time.is_some(),
+            |q| {
+                q.and_where(Expr::col(AuditEventQueryTable::CreatedAt).gte(start_time.unwrap()));
+            },
+            |_| {},
+        )
+        .conditions(
+            end_time.is_some(),
+            |q| {
+                q.and_where(Expr::col(AuditEventQueryTable::CreatedAt).lte(end_time.unwrap()));
+            },
+            |_| {},
+        )
+        .conditions(
+            cursor.is_some(),
+            |q| {
+                q.and_where(Expr::col(AuditEventQueryTable::Id).lt(cursor.unwrap().id));
+            },
+            |_| {},
+        )
+        .order_by(AuditEventQueryTable::Id, Order::Desc)
+        .limit(page_size + 1)
+        .to_string(MysqlQueryBuilder);
+
+    let mut result: Vec<AuditEvent> = sqlx::query_as(&query)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let next_cursor = if result.len() as u64 > page_size {
+        result.truncate(page_size as usize);
+        let last = result.last().ok_or_app_result("page is empty")?;
+        let cursor = AuditEventCursor {
+            id: last.id.ok_or_app_result("entity id is null")?,
+        };
+        Some(cursor.encode()?)
+    } else {
+        None
+    };
+
+    Ok(Page {
+        items: result,
+        next_cursor,
+    })
+}
@@ -0,0 +1,237 @@
+use sea_query::{Alias, Asterisk, Expr, Iden, MysqlQueryBuilder, Order, Query};
+use sqlx::{MySql, Transaction};
+use std::fmt::Write;
+
+use crate::extensions::OptionToAppResult;
+use crate::models::comment::Comment;
+use crate::models::comment_cursor::CommentCursor;
+use crate::models::cursor::Cursor;
+use crate::models::error::ApplicationError;
+use crate::models::page;
+use crate::models::page::Page;
+use crate::repositories::DatabaseConnection;
+
+enum CommentQueryTable {
+    Table,
+    Id,
+    EpisodeId,
+    AnimeId,
+    ParentId,
+    UserId,
+    IsHidden,
+    DeletedAt,
+    Users,
+    DisplayName,
+    IsBanned,
+}
+
+impl Iden for CommentQueryTable {
+    fn unquoted(&self, s: &mut dyn Write) {
+        let name = match self {
+            CommentQueryTable::Table => "comments",
+            CommentQueryTable::Id => "id",
+            CommentQueryTable::EpisodeId => "episode_id",
+            CommentQueryTable::AnimeId => "anime_id",
+            CommentQueryTable::ParentId => "parent_id",
+            CommentQueryTable::UserId => "user_id",
+            CommentQueryTable::IsHidden => "is_hidden",
+            CommentQueryTable::DeletedAt => "deleted_at",
+            CommentQueryTable::Users => "users",
+            CommentQueryTable::DisplayName => "display_name",
+            CommentQueryTable::IsBanned => "is_banned",
+        };
+
+        write!(s, "{}", name).unwrap()
+    }
+}
+
+const REPLY_COUNT_QUERY: &str = "(select count(*) from comments replies inner join users reply_users on reply_users.id = replies.user_id where replies.parent_id = comments.id and replies.deleted_at is null and replies.is_hidden = false and reply_users.is_banned = false)";
+const HAS_REPLIES_QUERY: &str = "exists (select 1 from comments replies where replies.parent_id = comments.id and replies.deleted_at is null)";
+
+pub struct CommentFilter {
+    pub episode_id: Option<String>,
+    pub anime_id: Option<u32>,
+    pub parent_id: Option<u64>,
+    pub page_size: Option<u32>,
+    pub cursor: Option<String>,
+}
+
+pub async fn comment_add(
+    conn: &DatabaseConnection,
+    comment: Comment,
+) -> Result<u64, ApplicationError> {
+    let id = sqlx::query("insert into comments (episode_id, anime_id, parent_id, user_id, content, is_spoiler, is_hidden, is_locked, created_at) values (?, ?, ?, ?, ?, ?, ?, ?, ?)")
+        .bind(comment.episode_id)
+        .bind(comment.anime_id)
+        .bind(comment.parent_id)
+        .bind(comment.user_id)
+        .bind(comment.content)
+        .bind(comment.is_spoiler)
+        .bind(comment.is_hidden)
+        .bind(comment.is_locked)
+        .bind(comment.created_at)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .last_insert_id();
+
+    Ok(id)
+}
+
+pub async fn comment_get_by_id(
+    conn: &DatabaseConnection,
+    id: u64,
+) -> Result<Comment, ApplicationError> {
+    let result = sqlx::query_as("select * from comments where id = ?")
+        .bind(id)
+        .fetch_optional(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    Ok(result)
+}
+
+pub async fn comment_update(
+    tx: &mut Transaction<'_, MySql>,
+    comment: Comment,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update comments set content = ?, is_spoiler = ?, is_hidden = ?, is_locked = ?, updated_at = ?, deleted_at = ? where id = ?")
+        .bind(comment.content)
+        .bind(comment.is_spoiler)
+        .bind(comment.is_hidden)
+        .bind(comment.is_locked)
+        .bind(comment.updated_at)
+        .bind(comment.deleted_at)
+        .bind(comment.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn comment_list(
+    conn: &DatabaseConnection,
+    filter: CommentFilter,
+    show_all: bool,
+) -> Result<Page<Comment>, ApplicationError> {
+    let CommentFilter {
+        episode_id,
+        anime_id,
+        parent_id,
+        page_size,
+        cursor,
+    } = filter;
+
+    let cursor = if let Some(cursor) = cursor {
+        Some(CommentCursor::decode(&cursor)?)
+    } else {
+        None
+    };
+    let page_size = page::page_size(page_size);
+    // Threads are listed newest first, replies in the order they were written.
+    let descending = parent_id.is_none();
+
+    let sql = Query::select()
+        .column((CommentQueryTable::Table, Asterisk))
+        .column((CommentQueryTable::Users, CommentQueryTable::DisplayName))
+        .expr_as(Expr::cust(REPLY_COUNT_QUERY), Alias::new("reply_count"))
+        .from(CommentQueryTable::Table)
+        .inner_join(
+            CommentQueryTable::Users,
+            Expr::col((CommentQueryTable::Users, CommentQueryTable::Id))
+                .equals((CommentQueryTable::Table, CommentQueryTable::UserId)),
+        )
+        .and_where(
+            Expr::col((CommentQueryTable::Table, CommentQueryTable::DeletedAt))
+                .is_null()
+                .or(Expr::cust(HAS_REPLIES_QUERY)),
+        )
+        .conditions(
+            episode_id.is_some(),
+            |q| {
+                q.and_where(
+                    Expr::col((CommentQueryTable::Table, CommentQueryTable::EpisodeId))
+                        .eq(episode_id.unwrap()),
+                );
+            },
+            |_| {},
+        )
+        .conditions(
+            anime_id.is_some(),
+            |q| {
+                q.and_where(
+                    Expr::col((CommentQueryTable::Table, CommentQueryTable::AnimeId))
+                        .eq(anime_id.unwrap()),
+                );
+            },
+            |_| {},
+        )
+        .conditions(
+            parent_id.is_some(),
+            |q| {
+                q.and_where(
+                    Expr::col((CommentQueryTable::Table, CommentQueryTable::ParentId))
+                        .eq(parent_id.unwrap()),
+                );
+            },
+            |q| {
+                q.and_where(
+                    Expr::col((CommentQueryTable::Table, CommentQueryTable::ParentId)).is_null(),
+                );
+            },
+        )
+        .conditions(
+            !show_all,
+            |q| {
+                q.and_where(
+                    Expr::col((CommentQueryTable::Table, CommentQueryTable::IsHidden)).eq(false),
+                )
+                .and_where(
+                    Expr::col((CommentQueryTable::Users, CommentQueryTable::IsBanned)).eq(false),
+                );
+            },
+            |_| {},
+        )
+        .conditions(
+            cursor.is_some(),
+            |q| {
+                let id = Expr::col((CommentQueryTable::Table, CommentQueryTable::Id));
+                let cursor = cursor.unwrap();
+                q.and_where(if descending {
+                    id.lt(cursor.id)
+                } else {
+                    id.gt(cursor.id)
+                });
+            },
+            |_| {},
+        )
+        .order_by(
+            (CommentQueryTable::Table, CommentQueryTable::Id),
+            if descending { Order::Desc } else { Order::Asc },
+        )
+        .limit(page_size + 1)
+        .to_string(MysqlQueryBuilder);
+
+    let mut result: Vec<Comment> = sqlx::query_as(&sql)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let next_cursor = if result.len() as u64 > page_size {
+        result.truncate(page_size as usize);
+        let last = result.last().ok_or_app_result("page is empty")?;
+        let cursor = CommentCursor {
+            id: last.id.unwrap_or(0),
+        };
+        Some(cursor.encode()?)
+    } else {
+        None
+    };
+
+    Ok(Page {
+        items: result,
+        next_cursor,
+    })
+}
@@ -0,0 +1,68 @@
+use crate::models::credentials::Credentials;
+use crate::models::error::ApplicationError;
+use crate::repositories::DatabaseConnection;
+
+pub async fn credentials_add(
+    conn: &DatabaseConnection,
+    credentials: Credentials,
+) -> Result<(), ApplicationError> {
+    sqlx::query("insert into user_credentials (user_id, email, password_hash, created_at, updated_at) values (?, ?, ?, ?, ?)")
+        .bind(credentials.user_id)
+        .bind(credentials.email)
+        .bind(credentials.password_hash)
+        .bind(credentials.created_at)
+        .bind(credentials.updated_at)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => ApplicationError::InvalidData(
+                anyhow::Error::msg("email or user already has credentials"),
+            ),
+            e => ApplicationError::UnknownError(e.into()),
+        })?;
+
+    Ok(())
+}
+
+pub async fn credentials_get_by_email(
+    conn: &DatabaseConnection,
+    email: &str,
+) -> Result<Credentials, ApplicationError> {
+    let result = sqlx::query_as("select * from user_credentials where email = ?")
+        .bind(email)
+        .fetch_optional(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    Ok(result)
+}
+
+pub async fn credentials_get_by_user(
+    conn: &DatabaseConnection,
+    user_id: &str,
+) -> Result<Credentials, ApplicationError> {
+    let result = sqlx::query_as("select * from user_credentials where user_id = ?")
+        .bind(user_id)
+        .fetch_optional(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    Ok(result)
+}
+
+pub async fn credentials_update_password(
+    conn: &DatabaseConnection,
+    credentials: Credentials,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update user_credentials set password_hash = ?, updated_at = ? where user_id = ?")
+        .bind(credentials.password_hash)
+        .bind(credentials.updated_at)
+        .bind(credentials.user_id)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use chrono::{DateTime, Utc};
+use sea_query::{Cond, Expr, Iden, MysqlQueryBuilder, Order, Query};
+use std::fmt::Write;
+
+use crate::extensions::OptionToAppResult;
+use crate::models::broken_episode::BrokenEpisode;
+use crate::models::broken_episode_cursor::BrokenEpisodeCursor;
+use crate::models::cursor::Cursor;
+use crate::models::episode::Episode;
+use crate::models::episode_health_check::EpisodeHealthCheck;
+use crate::models::error::ApplicationError;
+use crate::models::page;
+use crate::models::page::Page;
+use crate::repositories::DatabaseConnection;
+
+enum EpisodeHealthQueryTable {
+    Table,
+    EpisodeId,
+    IsHealthy,
+    LastError,
+    ConsecutiveFailures,
+    AutoHidden,
+    CheckedAt,
+    Episodes,
+    Id,
+    SeasonId,
+    SourceId,
+    Sequence,
+    MediaProvider,
+    MediaId,
+    IsHidden,
+    DeletedAt,
+}
+
+impl Iden for EpisodeHealthQueryTable {
+    fn unquoted(&self, s: &mut dyn Write) {
+        let name = match self {
+            EpisodeHealthQueryTable::Table => "episode_health_checks",
+            EpisodeHealthQueryTable::EpisodeId => "episode_id",
+            EpisodeHealthQueryTable::IsHealthy => "is_healthy",
+            EpisodeHealthQueryTable::LastError => "last_error",
+            EpisodeHealthQueryTable::ConsecutiveFailures => "consecutive_failures",
+            EpisodeHealthQueryTable::AutoHidden => "auto_hidden",
+            EpisodeHealthQueryTable::CheckedAt => "checked_at",
+            EpisodeHealthQueryTable::Episodes => "episodes",
+            EpisodeHealthQueryTable::Id => "id",
+            EpisodeHealthQueryTable::SeasonId => "season_id",
+            EpisodeHealthQueryTable::SourceId => "source_id",
+            EpisodeHealthQueryTable::Sequence => "sequence",
+            EpisodeHealthQueryTable::MediaProvider => "media_provider",
+            EpisodeHealthQueryTable::MediaId => "media_id",
+            EpisodeHealthQueryTable::IsHidden => "is_hidden",
+            EpisodeHealthQueryTable::DeletedAt => "deleted_at",
+        };
+
+        write!(s, "{}", name).unwrap()
+    }
+}
+
+pub async fn episode_get_due_for_check(
+    conn: &DatabaseConnection,
+    checked_before: DateTime<Utc>,
+    limit: u32,
+) -> Result<Vec<Episode>, ApplicationError> {
+    let result = sqlx::query_as("select episodes.* from episodes left join episode_health_checks on episode_health_checks.episode_id = episodes.id where episodes.media_id is not null and episodes.deleted_at is null and (episode_health_checks.checked_at is null or episode_health_checks.checked_at < ?) order by episode_health_checks.checked_at limit ?")
+        .bind(checked_before)
+        .bind(limit)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result)
+}
+
+pub async fn episode_health_check_get(
+    conn: &DatabaseConnection,
+    episode_id: &str,
+) -> Result<Option<EpisodeHealthCheck>, ApplicationError> {
+    let result = sqlx::query_as("select * from episode_health_checks where episode_id = ?")
+        .bind(episode_id)
+        .fetch_optional(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result)
+}
+
+pub async fn episode_health_check_save(
+    conn: &DatabaseConnection,
+    check: EpisodeHealthCheck,
+) -> Result<(), ApplicationError> {
+    sqlx::query("insert into episode_health_checks (episode_id, is_healthy, last_error, consecutive_failures, auto_hidden, checked_at) values (?, ?, ?, ?, ?, ?) on duplicate key update is_healthy = values(is_healthy), last_error = values(last_error), consecutive_failures = values(consecutive_failures), auto_hidden = values(auto_hidden), checked_at = values(checked_at)")
+        .bind(check.episode_id)
+        .bind(check.is_healthy)
+        .bind(check.last_error)
+        .bind(check.consecutive_failures)
+        .bind(check.auto_hidden)
+        .bind(check.checked_at)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn broken_episode_list(
+    conn: &DatabaseConnection,
+    page_size: Option<u32>,
+    cursor: Option<String>,
+) -> Result<Page<BrokenEpisode>, ApplicationError> {
+    let cursor = if let Some(cursor) = cursor {
+        Some(BrokenEpisodeCursor::decode(&cursor)?)
+    } else {
+        None
+    };
+    let page_size = page::page_size(page_size);
+    let sql = build_broken_episode_list_query(cursor, page_size);
+
+    let mut result: Vec<BrokenEpisode> = sqlx::query_as(&sql)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let next_cursor = if result.len() as u64 > page_size {
+        result.truncate(page_size as usize);
+        let last = result.last().ok_or_app_result("page is empty")?;
+        Some(
+            BrokenEpisodeCursor {
+                consecutive_failures: last.consecutive_failures,
+                episode_id: last.episode_id.clone(),
+            }
+            .encode()?,
+        )
+    } else {
+        None
+    };
+
+    Ok(Page {
+        items: result,
+        next_cursor,
+    })
+}
+
+fn build_broken_episode_list_query(cursor: Option<BrokenEpisodeCursor>, page_size: u64) -> String {
+    Query::select()
+        .columns([
+            (
+                EpisodeHealthQueryTable::Table,
+                EpisodeHealthQueryTable::EpisodeId,
+            ),
+            (
+                EpisodeHealthQueryTable::Table,
+                EpisodeHealthQueryTable::LastError,
+            ),
+            (
+                EpisodeHealthQueryTable::Table,
+                EpisodeHealthQueryTable::ConsecutiveFailures,
+            ),
+            (
+                EpisodeHealthQueryTable::Table,
+                EpisodeHealthQueryTable::AutoHidden,
+            ),
+            (
+                EpisodeHealthQueryTable::Table,
+                EpisodeHealthQueryTable::CheckedAt,
+            ),
+            (
+                EpisodeHealthQueryTable::Episodes,
+                EpisodeHealthQueryTable::SeasonId,
+            ),
+            (
+                EpisodeHealthQueryTable::Episodes,
+                EpisodeHealthQueryTable::SourceId,
+            ),
+            (
+                EpisodeHealthQueryTable::Episodes,
+                EpisodeHealthQueryTable::Sequence,
+            ),
+            (
+                EpisodeHealthQueryTable::Episodes,
+                EpisodeHealthQueryTable::MediaProvider,
+            ),
+            (
+                EpisodeHealthQueryTable::Episodes,
+                EpisodeHealthQueryTable::MediaId,
+            ),
+            (
+                EpisodeHealthQueryTable::Episodes,
+                EpisodeHealthQueryTable::IsHidden,
+            ),
+        ])
+        .from(EpisodeHealthQueryTable::Table)
+        .inner_join(
+            EpisodeHealthQueryTable::Episodes,
+            Expr::col((
+                EpisodeHealthQueryTable::Episodes,
+                EpisodeHealthQueryTable::Id,
+            ))
+            .equals((
+                EpisodeHealthQueryTable::Table,
+                EpisodeHealthQueryTable::EpisodeId,
+            )),
+        )
+        .and_where(
+            Expr::col((
+                EpisodeHealthQueryTable::Table,
+                EpisodeHealthQueryTable::IsHealthy,
+            ))
+            .eq(false),
+        )
+        .and_where(
+            Expr::col((
+                EpisodeHealthQueryTable::Episodes,
+                EpisodeHealthQueryTable::MediaId,
+            ))
+            .is_not_null(),
+        )
+        .and_where(
+            Expr::col((
+                EpisodeHealthQueryTable::Episodes,
+                EpisodeHealthQueryTable::DeletedAt,
+            ))
+            .is_null(),
+        )
+        .conditions(
+            cursor.is_some(),
+            |q| {
+                let cursor = cursor.unwrap();
+                q.cond_where(
+                    Cond::any()
+                        .add(
+                            Expr::col((
+                                EpisodeHealthQueryTable::Table,
+                                EpisodeHealthQueryTable::ConsecutiveFailures,
+                            ))
+                            .lt(cursor.consecutive_failures),
+                        )
+                        .add(
+                            Cond::all()
+                                .add(
+                                    Expr::col((
+                                        EpisodeHealthQueryTable::Table,
+                                        EpisodeHealthQueryTable::ConsecutiveFailures,
+                                    ))
+                                    .eq(cursor.consecutive_failures),
+                                )
+                                .add(
+                                    Expr::col((
+                                        EpisodeHealthQueryTable::Table,
+                                        EpisodeHealthQueryTable::EpisodeId,
+                                    ))
+                                    .gt(cursor.episode_id),
+                                ),
+                        ),
+                );
+            },
+            |_| {},
+        )
+        .order_by(
+            (
+                EpisodeHealthQueryTable::Table,
+                EpisodeHealthQueryTable::ConsecutiveFailures,
+            ),
+            Order::Desc,
+        )
+        .order_by(
+            (
+                EpisodeHealthQueryTable::Table,
+                EpisodeHealthQueryTable::EpisodeId,
+            ),
+            Order::Asc,
+        )
+        .limit(page_size + 1)
+        .to_string(MysqlQueryBuilder)
+}
@@ -1,11 +1,41 @@
+use async_stream::try_stream;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Transaction};
+use tokio_stream::{Stream, StreamExt};
+
 use crate::models::episode::Episode;
 use crate::models::error::ApplicationError;
-use crate::repositories::DatabaseConnection;
+use crate::repositories::{map_unique_violation, DatabaseConnection, EPISODE_SEQUENCE_IN_USE};
+
+async fn season_ensure_live(
+    tx: &mut Transaction<'_, MySql>,
+    season_id: u32,
+) -> Result<(), ApplicationError> {
+    let (season_deleted_at, anime_deleted_at): (Option<DateTime<Utc>>, Option<DateTime<Utc>>) =
+        sqlx::query_as(
+            "select seasons.deleted_at, animes.deleted_at from seasons join animes on animes.id = seasons.anime_id where seasons.id = ? for update",
+        )
+        .bind(season_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    if season_deleted_at.is_some() || anime_deleted_at.is_some() {
+        return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+            "parent is deleted",
+        )));
+    }
+
+    Ok(())
+}
 
 pub async fn episode_add(
-    conn: &DatabaseConnection,
+    tx: &mut Transaction<'_, MySql>,
     episode: Episode,
 ) -> Result<(), ApplicationError> {
+    season_ensure_live(tx, episode.season_id).await?;
+
     sqlx::query("insert into episodes (id, name, cover_id, season_id, source_id, is_nsfw, sequence, is_hidden) values (?, ?, ?, ?, ?, ?, ?, ?)")
         .bind(episode.id)
         .bind(episode.name)
@@ -15,9 +45,9 @@ pub async fn episode_add(
         .bind(episode.is_nsfw)
         .bind(episode.sequence)
         .bind(episode.is_hidden)
-        .execute(&conn.connection)
+        .execute(&mut **tx)
         .await
-        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        .map_err(map_unique_violation(EPISODE_SEQUENCE_IN_USE))?;
 
     Ok(())
 }
@@ -26,39 +56,64 @@ pub async fn episode_get_by_id(
     conn: &DatabaseConnection,
     id: &str,
 ) -> Result<Episode, ApplicationError> {
-    let result = sqlx::query_as("select * from episodes where id = ?")
+    let result = sqlx::query_as("select * from episodes where id = ? and deleted_at is null")
         .bind(id)
-        .fetch_one(&conn.connection)
+        .fetch_optional(&conn.connection)
         .await
-        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
 
     Ok(result)
 }
 
-pub async fn episode_update(
+pub async fn episode_get_visible_by_id(
     conn: &DatabaseConnection,
+    id: &str,
+    show_all: bool,
+) -> Result<Episode, ApplicationError> {
+    let result = sqlx::query_as("select episodes.* from episodes inner join seasons on seasons.id = episodes.season_id inner join animes on animes.id = seasons.anime_id where episodes.id = ? and episodes.deleted_at is null and seasons.deleted_at is null and animes.deleted_at is null and (? or (episodes.is_hidden = false and animes.is_hidden = false))")
+        .bind(id)
+        .bind(show_all)
+        .fetch_optional(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    Ok(result)
+}
+
+pub async fn episode_update(
+    tx: &mut Transaction<'_, MySql>,
     episode: Episode,
 ) -> Result<(), ApplicationError> {
-    sqlx::query("update episodes set cover_id = ?, lbry_media_id = ?, file_name = ?, sequence = ?, is_hidden = ? where id = ?")
+    sqlx::query("update episodes set cover_id = ?, media_id = ?, media_provider = ?, file_name = ?, duration_seconds = ?, width = ?, height = ?, thumbnail_url = ?, upload_date = ?, sequence = ?, is_hidden = ? where id = ? and deleted_at is null")
         .bind(episode.cover_id)
-        .bind(episode.lbry_media_id)
+        .bind(episode.media_id)
+        .bind(episode.media_provider as u8)
         .bind(episode.file_name)
+        .bind(episode.duration_seconds)
+        .bind(episode.width)
+        .bind(episode.height)
+        .bind(episode.thumbnail_url)
+        .bind(episode.upload_date)
         .bind(episode.sequence)
         .bind(episode.is_hidden)
         .bind(episode.id)
-        .execute(&conn.connection)
+        .execute(&mut **tx)
         .await
-        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        .map_err(map_unique_violation(EPISODE_SEQUENCE_IN_USE))?;
 
     Ok(())
 }
 
+const EPISODES_BY_SEASON_AND_SOURCE_QUERY: &str = "select * from episodes where season_id = ? and source_id = ? and media_id is not null and file_name is not null and is_hidden = false and deleted_at is null order by sequence";
+
 pub async fn episode_get_by_season_and_source(
     conn: &DatabaseConnection,
     season_id: u32,
     source_id: u32,
 ) -> Result<Vec<Episode>, ApplicationError> {
-    let result = sqlx::query_as("select * from episodes where season_id = ? and source_id = ? and lbry_media_id is not null and file_name is not null and is_hidden = false order by sequence")
+    let result = sqlx::query_as(EPISODES_BY_SEASON_AND_SOURCE_QUERY)
         .bind(season_id)
         .bind(source_id)
         .fetch_all(&conn.connection)
@@ -67,3 +122,123 @@ pub async fn episode_get_by_season_and_source(
 
     Ok(result)
 }
+
+pub async fn episode_delete(
+    tx: &mut Transaction<'_, MySql>,
+    episode: Episode,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update episodes set deleted_at = ? where id = ? and deleted_at is null")
+        .bind(episode.deleted_at)
+        .bind(episode.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn episode_restore(
+    tx: &mut Transaction<'_, MySql>,
+    id: &str,
+) -> Result<(), ApplicationError> {
+    let season_id: u32 = sqlx::query_scalar(
+        "select season_id from episodes where id = ? and deleted_at is not null for update",
+    )
+    .bind(id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?
+    .ok_or(ApplicationError::NotFound)?;
+
+    season_ensure_live(tx, season_id).await?;
+
+    let result = sqlx::query(
+        "update episodes set deleted_at = null where id = ? and deleted_at is not null",
+    )
+    .bind(id)
+    .execute(&mut **tx)
+    .await
+    .map_err(map_unique_violation(EPISODE_SEQUENCE_IN_USE))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApplicationError::NotFound);
+    }
+
+    Ok(())
+}
+
+pub async fn episode_purge(
+    tx: &mut Transaction<'_, MySql>,
+    id: &str,
+) -> Result<(), ApplicationError> {
+    let result = sqlx::query("delete from episodes where id = ? and deleted_at is not null")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApplicationError::NotFound);
+    }
+
+    Ok(())
+}
+
+pub fn episode_get_by_season_and_source_stream(
+    conn: &DatabaseConnection,
+    season_id: u32,
+    source_id: u32,
+) -> impl Stream<Item = Result<Episode, ApplicationError>> {
+    let pool = conn.connection.clone();
+
+    try_stream! {
+        let mut rows = sqlx::query_as::<_, Episode>(EPISODES_BY_SEASON_AND_SOURCE_QUERY)
+            .bind(season_id)
+            .bind(source_id)
+            .fetch(&pool);
+        while let Some(episode) = rows.next().await {
+            yield episode.map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        }
+    }
+}
+
+pub async fn episode_update_media(
+    conn: &DatabaseConnection,
+    episode: &Episode,
+) -> Result<bool, ApplicationError> {
+    let result = sqlx::query("update episodes set file_name = ?, duration_seconds = ?, width = ?, height = ?, thumbnail_url = ?, upload_date = ? where id = ? and media_id = ? and media_provider = ? and deleted_at is null")
+        .bind(&episode.file_name)
+        .bind(episode.duration_seconds)
+        .bind(episode.width)
+        .bind(episode.height)
+        .bind(&episode.thumbnail_url)
+        .bind(episode.upload_date)
+        .bind(&episode.id)
+        .bind(&episode.media_id)
+        .bind(episode.media_provider as u8)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Only flips the flag if the episode still has the media and visibility it was checked with,
+/// returning whether it did.
+pub async fn episode_set_hidden(
+    conn: &DatabaseConnection,
+    episode: &Episode,
+    is_hidden: bool,
+) -> Result<bool, ApplicationError> {
+    let result = sqlx::query("update episodes set is_hidden = ? where id = ? and media_id = ? and media_provider = ? and is_hidden = ? and deleted_at is null")
+        .bind(is_hidden)
+        .bind(&episode.id)
+        .bind(&episode.media_id)
+        .bind(episode.media_provider as u8)
+        .bind(episode.is_hidden)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -1,12 +1,37 @@
 use crate::models::config::Config;
 use crate::models::error::ApplicationError;
-use sqlx::{MySql, MySqlPool, Pool};
+use sqlx::{MySql, MySqlPool, Pool, Transaction};
 
+pub mod anime_list_entry_repository;
+pub mod anime_rating_repository;
 pub mod anime_repository;
+pub mod anime_revision_repository;
+pub mod audit_repository;
+pub mod comment_repository;
+pub mod credentials_repository;
+pub mod episode_health_repository;
 pub mod episode_repository;
+pub mod notification_repository;
+pub mod refresh_token_repository;
 pub mod season_repository;
+pub mod session_repository;
 pub mod source_repository;
 pub mod user_repository;
+pub mod watch_progress_repository;
+
+const SEASON_SEQUENCE_IN_USE: &str = "season sequence is already in use";
+const SOURCE_ALREADY_EXISTS: &str = "a source with this name and type already exists";
+const EPISODE_SEQUENCE_IN_USE: &str =
+    "episode sequence is already in use for this season and source";
+
+fn map_unique_violation(message: &'static str) -> impl Fn(sqlx::Error) -> ApplicationError {
+    move |e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            ApplicationError::InvalidData(anyhow::Error::msg(message))
+        }
+        e => ApplicationError::UnknownError(e.into()),
+    }
+}
 
 pub struct DatabaseConnection {
     connection: Pool<MySql>,
@@ -20,6 +45,13 @@ impl DatabaseConnection {
         Self { connection: conn }
     }
 
+    pub async fn begin(&self) -> Result<Transaction<'static, MySql>, ApplicationError> {
+        self.connection
+            .begin()
+            .await
+            .map_err(|e| ApplicationError::UnknownError(e.into()))
+    }
+
     pub async fn migrate_database(&self) -> Result<(), ApplicationError> {
         sqlx::migrate!()
             .run(&self.connection)
@@ -29,3 +61,9 @@ impl DatabaseConnection {
         Ok(())
     }
 }
+
+pub async fn commit(tx: Transaction<'_, MySql>) -> Result<(), ApplicationError> {
+    tx.commit()
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))
+}
@@ -0,0 +1,448 @@
+use chrono::{DateTime, Utc};
+use sea_query::{Expr, Iden, MysqlQueryBuilder, Order, Query};
+use sqlx::{MySql, Row, Transaction};
+use std::fmt::Write;
+
+use crate::extensions::OptionToAppResult;
+use crate::models::cursor::Cursor;
+use crate::models::error::ApplicationError;
+use crate::models::follow_cursor::FollowCursor;
+use crate::models::notification::Notification;
+use crate::models::notification_cursor::NotificationCursor;
+use crate::models::page;
+use crate::models::page::Page;
+use crate::repositories::DatabaseConnection;
+
+enum NotificationQueryTable {
+    Table,
+    Id,
+    UserId,
+    AnimeId,
+    EpisodeId,
+    CreatedAt,
+    ReadAt,
+    Animes,
+    MainTitle,
+    Episodes,
+    SeasonId,
+    Sequence,
+    AnimeFollows,
+    IsHidden,
+    DeletedAt,
+}
+
+impl Iden for NotificationQueryTable {
+    fn unquoted(&self, s: &mut dyn Write) {
+        let name = match self {
+            NotificationQueryTable::Table => "notifications",
+            NotificationQueryTable::Id => "id",
+            NotificationQueryTable::UserId => "user_id",
+            NotificationQueryTable::AnimeId => "anime_id",
+            NotificationQueryTable::EpisodeId => "episode_id",
+            NotificationQueryTable::CreatedAt => "created_at",
+            NotificationQueryTable::ReadAt => "read_at",
+            NotificationQueryTable::Animes => "animes",
+            NotificationQueryTable::MainTitle => "main_title",
+            NotificationQueryTable::Episodes => "episodes",
+            NotificationQueryTable::SeasonId => "season_id",
+            NotificationQueryTable::Sequence => "sequence",
+            NotificationQueryTable::AnimeFollows => "anime_follows",
+            NotificationQueryTable::IsHidden => "is_hidden",
+            NotificationQueryTable::DeletedAt => "deleted_at",
+        };
+
+        write!(s, "{}", name).unwrap()
+    }
+}
+
+pub async fn follow_add(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    anime_id: u32,
+) -> Result<(), ApplicationError> {
+    sqlx::query(
+        "insert ignore into anime_follows (user_id, anime_id, created_at) values (?, ?, ?)",
+    )
+    .bind(user_id)
+    .bind(anime_id)
+    .bind(Utc::now())
+    .execute(&conn.connection)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn follow_delete(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    anime_id: u32,
+) -> Result<(), ApplicationError> {
+    let result = sqlx::query("delete from anime_follows where user_id = ? and anime_id = ?")
+        .bind(user_id)
+        .bind(anime_id)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApplicationError::NotFound);
+    }
+
+    Ok(())
+}
+
+pub async fn follow_list(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    page_size: Option<u32>,
+    cursor: Option<String>,
+) -> Result<Page<u32>, ApplicationError> {
+    let cursor = if let Some(cursor) = cursor {
+        Some(FollowCursor::decode(&cursor)?)
+    } else {
+        None
+    };
+    let cursor_created_at = if let Some(cursor) = &cursor {
+        Some(DateTime::from_timestamp(cursor.created_at, 0).ok_or(
+            ApplicationError::InvalidData(anyhow::Error::msg("cursor is invalid")),
+        )?)
+    } else {
+        None
+    };
+    let page_size = page::page_size(page_size);
+
+    let sql = Query::select()
+        .columns([
+            (
+                NotificationQueryTable::AnimeFollows,
+                NotificationQueryTable::AnimeId,
+            ),
+            (
+                NotificationQueryTable::AnimeFollows,
+                NotificationQueryTable::CreatedAt,
+            ),
+        ])
+        .from(NotificationQueryTable::AnimeFollows)
+        .inner_join(
+            NotificationQueryTable::Animes,
+            Expr::col((NotificationQueryTable::Animes, NotificationQueryTable::Id)).equals((
+                NotificationQueryTable::AnimeFollows,
+                NotificationQueryTable::AnimeId,
+            )),
+        )
+        .and_where(
+            Expr::col((
+                NotificationQueryTable::AnimeFollows,
+                NotificationQueryTable::UserId,
+            ))
+            .eq(user_id),
+        )
+        .and_where(
+            Expr::col((
+                NotificationQueryTable::Animes,
+                NotificationQueryTable::DeletedAt,
+            ))
+            .is_null(),
+        )
+        .conditions(
+            cursor.is_some(),
+            |q| {
+                let cursor = cursor.unwrap();
+                let created_at = cursor_created_at.unwrap();
+                let created_at_col = Expr::col((
+                    NotificationQueryTable::AnimeFollows,
+                    NotificationQueryTable::CreatedAt,
+                ));
+                q.and_where(
+                    created_at_col
+                        .clone()
+                        .lt(created_at)
+                        .or(created_at_col.eq(created_at).and(
+                            Expr::col((
+                                NotificationQueryTable::AnimeFollows,
+                                NotificationQueryTable::AnimeId,
+                            ))
+                            .lt(cursor.anime_id),
+                        )),
+                );
+            },
+            |_| {},
+        )
+        .order_by(
+            (
+                NotificationQueryTable::AnimeFollows,
+                NotificationQueryTable::CreatedAt,
+            ),
+            Order::Desc,
+        )
+        .order_by(
+            (
+                NotificationQueryTable::AnimeFollows,
+                NotificationQueryTable::AnimeId,
+            ),
+            Order::Desc,
+        )
+        .limit(page_size + 1)
+        .to_string(MysqlQueryBuilder);
+
+    let mut result = sqlx::query(&sql)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .iter()
+        .map(|row| Ok((row.try_get("anime_id")?, row.try_get("created_at")?)))
+        .collect::<Result<Vec<(u32, DateTime<Utc>)>, sqlx::Error>>()
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let next_cursor = if result.len() as u64 > page_size {
+        result.truncate(page_size as usize);
+        let (anime_id, created_at) = result.last().ok_or_app_result("page is empty")?;
+        let cursor = FollowCursor {
+            created_at: created_at.timestamp(),
+            anime_id: *anime_id,
+        };
+        Some(cursor.encode()?)
+    } else {
+        None
+    };
+
+    Ok(Page {
+        items: result.into_iter().map(|(anime_id, _)| anime_id).collect(),
+        next_cursor,
+    })
+}
+
+pub async fn notification_add_for_episode(
+    tx: &mut Transaction<'_, MySql>,
+    episode_id: &str,
+    season_id: u32,
+) -> Result<(), ApplicationError> {
+    sqlx::query("insert ignore into notifications (user_id, anime_id, episode_id, created_at) select anime_follows.user_id, animes.id, ?, ? from seasons inner join animes on animes.id = seasons.anime_id inner join anime_follows on anime_follows.anime_id = animes.id where seasons.id = ? and seasons.deleted_at is null and animes.deleted_at is null and animes.is_hidden = false")
+        .bind(episode_id)
+        .bind(Utc::now())
+        .bind(season_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn notification_list(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    unread_only: bool,
+    page_size: Option<u32>,
+    cursor: Option<String>,
+) -> Result<Page<Notification>, ApplicationError> {
+    let cursor = if let Some(cursor) = cursor {
+        Some(NotificationCursor::decode(&cursor)?)
+    } else {
+        None
+    };
+    let page_size = page::page_size(page_size);
+
+    let sql = Query::select()
+        .columns([
+            (NotificationQueryTable::Table, NotificationQueryTable::Id),
+            (
+                NotificationQueryTable::Table,
+                NotificationQueryTable::UserId,
+            ),
+            (
+                NotificationQueryTable::Table,
+                NotificationQueryTable::AnimeId,
+            ),
+            (
+                NotificationQueryTable::Table,
+                NotificationQueryTable::EpisodeId,
+            ),
+            (
+                NotificationQueryTable::Table,
+                NotificationQueryTable::CreatedAt,
+            ),
+            (
+                NotificationQueryTable::Table,
+                NotificationQueryTable::ReadAt,
+            ),
+            (
+                NotificationQueryTable::Animes,
+                NotificationQueryTable::MainTitle,
+            ),
+            (
+                NotificationQueryTable::Episodes,
+                NotificationQueryTable::SeasonId,
+            ),
+            (
+                NotificationQueryTable::Episodes,
+                NotificationQueryTable::Sequence,
+            ),
+        ])
+        .from(NotificationQueryTable::Table)
+        .inner_join(
+            NotificationQueryTable::Animes,
+            Expr::col((NotificationQueryTable::Animes, NotificationQueryTable::Id)).equals((
+                NotificationQueryTable::Table,
+                NotificationQueryTable::AnimeId,
+            )),
+        )
+        .inner_join(
+            NotificationQueryTable::Episodes,
+            Expr::col((NotificationQueryTable::Episodes, NotificationQueryTable::Id)).equals((
+                NotificationQueryTable::Table,
+                NotificationQueryTable::EpisodeId,
+            )),
+        )
+        .and_where(
+            Expr::col((
+                NotificationQueryTable::Table,
+                NotificationQueryTable::UserId,
+            ))
+            .eq(user_id),
+        )
+        .and_where(
+            Expr::col((
+                NotificationQueryTable::Episodes,
+                NotificationQueryTable::IsHidden,
+            ))
+            .eq(false),
+        )
+        .and_where(
+            Expr::col((
+                NotificationQueryTable::Episodes,
+                NotificationQueryTable::DeletedAt,
+            ))
+            .is_null(),
+        )
+        .and_where(
+            Expr::col((
+                NotificationQueryTable::Animes,
+                NotificationQueryTable::IsHidden,
+            ))
+            .eq(false),
+        )
+        .and_where(
+            Expr::col((
+                NotificationQueryTable::Animes,
+                NotificationQueryTable::DeletedAt,
+            ))
+            .is_null(),
+        )
+        .conditions(
+            unread_only,
+            |q| {
+                q.and_where(
+                    Expr::col((
+                        NotificationQueryTable::Table,
+                        NotificationQueryTable::ReadAt,
+                    ))
+                    .is_null(),
+                );
+            },
+            |_| {},
+        )
+        .conditions(
+            cursor.is_some(),
+            |q| {
+                q.and_where(
+                    Expr::col((NotificationQueryTable::Table, NotificationQueryTable::Id))
+                        .lt(cursor.unwrap().id),
+                );
+            },
+            |_| {},
+        )
+        .order_by(
+            (NotificationQueryTable::Table, NotificationQueryTable::Id),
+            Order::Desc,
+        )
+        .limit(page_size + 1)
+        .to_string(MysqlQueryBuilder);
+
+    let mut result: Vec<Notification> = sqlx::query_as(&sql)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let next_cursor = if result.len() as u64 > page_size {
+        result.truncate(page_size as usize);
+        let last = result.last().ok_or_app_result("page is empty")?;
+        Some(NotificationCursor { id: last.id }.encode()?)
+    } else {
+        None
+    };
+
+    Ok(Page {
+        items: result,
+        next_cursor,
+    })
+}
+
+pub async fn notification_count_unread(
+    conn: &DatabaseConnection,
+    user_id: &str,
+) -> Result<u64, ApplicationError> {
+    let count: i64 = sqlx::query_scalar(
+        "select count(*) from notifications inner join animes on animes.id = notifications.anime_id inner join episodes on episodes.id = notifications.episode_id where notifications.user_id = ? and notifications.read_at is null and episodes.is_hidden = false and episodes.deleted_at is null and animes.is_hidden = false and animes.deleted_at is null",
+    )
+    .bind(user_id)
+    .fetch_one(&conn.connection)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(count as u64)
+}
+
+pub async fn notification_mark_read(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    ids: Option<Vec<u64>>,
+) -> Result<(), ApplicationError> {
+    let sql = build_mark_read_query(user_id, ids);
+
+    sqlx::query(&sql)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn notification_clear(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    read_only: bool,
+) -> Result<(), ApplicationError> {
+    let sql = build_clear_query(user_id, read_only);
+
+    sqlx::query(&sql)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+fn build_mark_read_query(user_id: &str, ids: Option<Vec<u64>>) -> String {
+    let mut query = Query::update();
+    query
+        .table(NotificationQueryTable::Table)
+        .value(NotificationQueryTable::ReadAt, Utc::now())
+        .and_where(Expr::col(NotificationQueryTable::UserId).eq(user_id))
+        .and_where(Expr::col(NotificationQueryTable::ReadAt).is_null());
+    if let Some(ids) = ids {
+        query.and_where(Expr::col(NotificationQueryTable::Id).is_in(ids));
+    }
+    query.to_string(MysqlQueryBuilder)
+}
+
+fn build_clear_query(user_id: &str, read_only: bool) -> String {
+    let mut query = Query::delete();
+    query
+        .from_table(NotificationQueryTable::Table)
+        .and_where(Expr::col(NotificationQueryTable::UserId).eq(user_id));
+    if read_only {
+        query.and_where(Expr::col(NotificationQueryTable::ReadAt).is_not_null());
+    }
+    query.to_string(MysqlQueryBuilder)
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Transaction};
+
+use crate::models::error::ApplicationError;
+use crate::models::refresh_token::RefreshToken;
+use crate::repositories::DatabaseConnection;
+
+pub async fn refresh_token_add(
+    tx: &mut Transaction<'_, MySql>,
+    refresh_token: &RefreshToken,
+) -> Result<(), ApplicationError> {
+    sqlx::query("insert into refresh_tokens (id, session_id, token_hash, created_at, expires_at) values (?, ?, ?, ?, ?)")
+        .bind(&refresh_token.id)
+        .bind(&refresh_token.session_id)
+        .bind(&refresh_token.token_hash)
+        .bind(refresh_token.created_at)
+        .bind(refresh_token.expires_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn refresh_token_get_by_hash(
+    conn: &DatabaseConnection,
+    token_hash: &str,
+) -> Result<RefreshToken, ApplicationError> {
+    let result = sqlx::query_as("select * from refresh_tokens where token_hash = ?")
+        .bind(token_hash)
+        .fetch_optional(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    Ok(result)
+}
+
+pub async fn refresh_token_rotate(
+    tx: &mut Transaction<'_, MySql>,
+    used: &RefreshToken,
+    replacement: &RefreshToken,
+) -> Result<bool, ApplicationError> {
+    let affected = sqlx::query("update refresh_tokens set used_at = ? where id = ? and used_at is null and revoked_at is null")
+        .bind(Utc::now())
+        .bind(&used.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .rows_affected();
+
+    if affected == 0 {
+        return Ok(false);
+    }
+
+    sqlx::query("insert into refresh_tokens (id, session_id, token_hash, created_at, expires_at) values (?, ?, ?, ?, ?)")
+        .bind(&replacement.id)
+        .bind(&replacement.session_id)
+        .bind(&replacement.token_hash)
+        .bind(replacement.created_at)
+        .bind(replacement.expires_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(true)
+}
+
+pub async fn refresh_token_revoke_family(
+    tx: &mut Transaction<'_, MySql>,
+    session_id: &str,
+    revoked_at: DateTime<Utc>,
+) -> Result<(), ApplicationError> {
+    sqlx::query(
+        "update refresh_tokens set revoked_at = ? where session_id = ? and revoked_at is null",
+    )
+    .bind(revoked_at)
+    .bind(session_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    sqlx::query("update sessions set revoked_at = ? where id = ? and revoked_at is null")
+        .bind(revoked_at)
+        .bind(session_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
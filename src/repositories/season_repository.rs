@@ -1,22 +1,50 @@
-use sqlx::Row;
+use async_stream::try_stream;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Row, Transaction};
+use tokio_stream::{Stream, StreamExt};
 
 use crate::models::error::ApplicationError;
 use crate::models::season::Season;
-use crate::repositories::DatabaseConnection;
+use crate::repositories::{
+    map_unique_violation, DatabaseConnection, EPISODE_SEQUENCE_IN_USE, SEASON_SEQUENCE_IN_USE,
+};
+
+async fn anime_ensure_live(
+    tx: &mut Transaction<'_, MySql>,
+    anime_id: u32,
+) -> Result<(), ApplicationError> {
+    let deleted_at: Option<DateTime<Utc>> =
+        sqlx::query_scalar("select deleted_at from animes where id = ? for update")
+            .bind(anime_id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| ApplicationError::UnknownError(e.into()))?
+            .ok_or(ApplicationError::NotFound)?;
+
+    if deleted_at.is_some() {
+        return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+            "parent is deleted",
+        )));
+    }
+
+    Ok(())
+}
 
 pub async fn season_add(
-    conn: &DatabaseConnection,
+    tx: &mut Transaction<'_, MySql>,
     season: Season,
 ) -> Result<u32, ApplicationError> {
+    anime_ensure_live(tx, season.anime_id).await?;
+
     let id =
         sqlx::query("insert into seasons (name, cover_id, anime_id, sequence) values (?, ?, ?, ?)")
             .bind(season.name)
             .bind(season.cover_id)
             .bind(season.anime_id)
             .bind(season.sequence)
-            .execute(&conn.connection)
+            .execute(&mut **tx)
             .await
-            .map_err(|e| ApplicationError::UnknownError(e.into()))?
+            .map_err(map_unique_violation(SEASON_SEQUENCE_IN_USE))?
             .last_insert_id();
 
     Ok(id as u32)
@@ -27,7 +55,7 @@ pub async fn season_get_last_sequence(
     anime_id: u32,
 ) -> Result<u16, ApplicationError> {
     let result = sqlx::query(
-        "select sequence from seasons where anime_id = ? order by sequence desc limit 1",
+        "select sequence from seasons where anime_id = ? and deleted_at is null order by sequence desc limit 1",
     )
     .bind(anime_id)
     .fetch_optional(&conn.connection)
@@ -40,11 +68,14 @@ pub async fn season_get_last_sequence(
         .map_err(|e| ApplicationError::UnknownError(e.into()))
 }
 
+const SEASONS_BY_ANIME_QUERY: &str =
+    "select * from seasons where anime_id = ? and deleted_at is null order by sequence";
+
 pub async fn season_get_by_anime(
     conn: &DatabaseConnection,
     anime_id: u32,
 ) -> Result<Vec<Season>, ApplicationError> {
-    let result = sqlx::query_as("select * from seasons where anime_id = ? order by sequence")
+    let result = sqlx::query_as(SEASONS_BY_ANIME_QUERY)
         .bind(anime_id)
         .fetch_all(&conn.connection)
         .await
@@ -53,18 +84,34 @@ pub async fn season_get_by_anime(
     Ok(result)
 }
 
-pub async fn season_update(
+pub fn season_get_by_anime_stream(
     conn: &DatabaseConnection,
+    anime_id: u32,
+) -> impl Stream<Item = Result<Season, ApplicationError>> {
+    let pool = conn.connection.clone();
+
+    try_stream! {
+        let mut rows = sqlx::query_as::<_, Season>(SEASONS_BY_ANIME_QUERY)
+            .bind(anime_id)
+            .fetch(&pool);
+        while let Some(season) = rows.next().await {
+            yield season.map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        }
+    }
+}
+
+pub async fn season_update(
+    tx: &mut Transaction<'_, MySql>,
     season: Season,
 ) -> Result<(), ApplicationError> {
-    sqlx::query("update seasons set name = ?, cover_id = ?, sequence = ? where id = ?")
+    sqlx::query("update seasons set name = ?, cover_id = ?, sequence = ? where id = ? and deleted_at is null")
         .bind(season.name)
         .bind(season.cover_id)
         .bind(season.sequence)
         .bind(season.id)
-        .execute(&conn.connection)
+        .execute(&mut **tx)
         .await
-        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        .map_err(map_unique_violation(SEASON_SEQUENCE_IN_USE))?;
 
     Ok(())
 }
@@ -73,7 +120,7 @@ pub async fn season_bu_id(
     conn: &DatabaseConnection,
     season_id: u32,
 ) -> Result<Season, ApplicationError> {
-    let result = sqlx::query_as("select * from seasons where id = ?")
+    let result = sqlx::query_as("select * from seasons where id = ? and deleted_at is null")
         .bind(season_id)
         .fetch_optional(&conn.connection)
         .await
@@ -82,3 +129,81 @@ pub async fn season_bu_id(
 
     Ok(result)
 }
+
+pub async fn season_delete(
+    tx: &mut Transaction<'_, MySql>,
+    season: Season,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update seasons set deleted_at = ? where id = ? and deleted_at is null")
+        .bind(season.deleted_at)
+        .bind(season.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    sqlx::query("update episodes set deleted_at = ? where season_id = ? and deleted_at is null")
+        .bind(season.deleted_at)
+        .bind(season.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn season_restore(
+    tx: &mut Transaction<'_, MySql>,
+    id: u32,
+) -> Result<(), ApplicationError> {
+    let (anime_id, deleted_at): (u32, DateTime<Utc>) = sqlx::query_as(
+        "select anime_id, deleted_at from seasons where id = ? and deleted_at is not null",
+    )
+    .bind(id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?
+    .ok_or(ApplicationError::NotFound)?;
+
+    anime_ensure_live(tx, anime_id).await?;
+
+    sqlx::query("update episodes set deleted_at = null where season_id = ? and deleted_at = ?")
+        .bind(id)
+        .bind(deleted_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(map_unique_violation(EPISODE_SEQUENCE_IN_USE))?;
+
+    sqlx::query("update seasons set deleted_at = null where id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(map_unique_violation(SEASON_SEQUENCE_IN_USE))?;
+
+    Ok(())
+}
+
+pub async fn season_purge(
+    tx: &mut Transaction<'_, MySql>,
+    id: u32,
+) -> Result<(), ApplicationError> {
+    sqlx::query("select id from seasons where id = ? and deleted_at is not null for update")
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    sqlx::query("delete from episodes where season_id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    sqlx::query("delete from seasons where id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
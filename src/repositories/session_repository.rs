@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Transaction};
+
+use crate::models::error::ApplicationError;
+use crate::models::session::Session;
+use crate::repositories::DatabaseConnection;
+
+pub async fn session_add(
+    tx: &mut Transaction<'_, MySql>,
+    session: &Session,
+) -> Result<(), ApplicationError> {
+    sqlx::query("insert into sessions (id, user_id, created_at, expires_at) values (?, ?, ?, ?)")
+        .bind(&session.id)
+        .bind(&session.user_id)
+        .bind(session.created_at)
+        .bind(session.expires_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn session_get_by_id(
+    conn: &DatabaseConnection,
+    id: &str,
+) -> Result<Session, ApplicationError> {
+    let result = sqlx::query_as("select * from sessions where id = ?")
+        .bind(id)
+        .fetch_optional(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    Ok(result)
+}
+
+pub async fn session_get_for_update(
+    tx: &mut Transaction<'_, MySql>,
+    id: &str,
+) -> Result<Session, ApplicationError> {
+    let result = sqlx::query_as("select * from sessions where id = ? for update")
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    Ok(result)
+}
+
+pub async fn session_get_active_by_user(
+    conn: &DatabaseConnection,
+    user_id: &str,
+) -> Result<Vec<Session>, ApplicationError> {
+    let result = sqlx::query_as("select * from sessions where user_id = ? and revoked_at is null and expires_at > ? order by created_at desc")
+        .bind(user_id)
+        .bind(Utc::now())
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result)
+}
+
+pub async fn session_revoke(
+    conn: &DatabaseConnection,
+    session: Session,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update sessions set revoked_at = ? where id = ? and revoked_at is null")
+        .bind(session.revoked_at)
+        .bind(session.id)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn session_revoke_all(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    revoked_at: DateTime<Utc>,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update sessions set revoked_at = ? where user_id = ? and revoked_at is null")
+        .bind(revoked_at)
+        .bind(user_id)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn session_revoke_others(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    current_session_id: &str,
+    revoked_at: DateTime<Utc>,
+) -> Result<(), ApplicationError> {
+    sqlx::query(
+        "update sessions set revoked_at = ? where user_id = ? and id <> ? and revoked_at is null",
+    )
+    .bind(revoked_at)
+    .bind(user_id)
+    .bind(current_session_id)
+    .execute(&conn.connection)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn legacy_token_exchange_add(
+    tx: &mut Transaction<'_, MySql>,
+    token_hash: &str,
+    user_id: &str,
+) -> Result<(), ApplicationError> {
+    sqlx::query(
+        "insert into legacy_token_exchanges (token_hash, user_id, exchanged_at) values (?, ?, ?)",
+    )
+    .bind(token_hash)
+    .bind(user_id)
+    .bind(Utc::now())
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => ApplicationError::Unauthorized,
+        e => ApplicationError::UnknownError(e.into()),
+    })?;
+
+    Ok(())
+}
@@ -1,9 +1,15 @@
 use crate::arkalis_service::GetSourcesRequest;
 use crate::models::error::ApplicationError;
 use crate::models::source::Source;
-use crate::repositories::DatabaseConnection;
+use crate::repositories::{
+    map_unique_violation, DatabaseConnection, EPISODE_SEQUENCE_IN_USE, SOURCE_ALREADY_EXISTS,
+};
+use async_stream::try_stream;
+use chrono::{DateTime, Utc};
 use sea_query::{Expr, Iden, MysqlQueryBuilder, Query};
+use sqlx::{MySql, Transaction};
 use std::fmt::Write;
+use tokio_stream::{Stream, StreamExt};
 
 enum SourceQueryTable {
     Table,
@@ -11,6 +17,7 @@ enum SourceQueryTable {
     Name,
     SourceType,
     Priority,
+    DeletedAt,
 }
 
 impl Iden for SourceQueryTable {
@@ -21,6 +28,7 @@ impl Iden for SourceQueryTable {
             SourceQueryTable::Name => "name",
             SourceQueryTable::SourceType => "source_type",
             SourceQueryTable::Priority => "priority",
+            SourceQueryTable::DeletedAt => "deleted_at",
         };
 
         write!(s, "{}", name).unwrap()
@@ -28,16 +36,16 @@ impl Iden for SourceQueryTable {
 }
 
 pub async fn source_add(
-    conn: &DatabaseConnection,
+    tx: &mut Transaction<'_, MySql>,
     source: Source,
 ) -> Result<u32, ApplicationError> {
     let id = sqlx::query("insert into sources (name, source_type, priority) values (?, ?, ?)")
         .bind(source.name)
         .bind(source.source_type.bits())
         .bind(source.priority)
-        .execute(&conn.connection)
+        .execute(&mut **tx)
         .await
-        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .map_err(map_unique_violation(SOURCE_ALREADY_EXISTS))?
         .last_insert_id();
 
     Ok(id as u32)
@@ -47,42 +55,7 @@ pub async fn source_get(
     conn: &DatabaseConnection,
     filters: GetSourcesRequest,
 ) -> Result<Vec<Source>, ApplicationError> {
-    let GetSourcesRequest {
-        source_type,
-        name,
-        priority,
-    } = filters;
-
-    let query = Query::select()
-        .columns([
-            SourceQueryTable::Id,
-            SourceQueryTable::Name,
-            SourceQueryTable::SourceType,
-            SourceQueryTable::Priority,
-        ])
-        .from(SourceQueryTable::Table)
-        .conditions(
-            source_type.is_some(),
-            move |q| {
-                q.and_where(Expr::col(SourceQueryTable::SourceType).eq(source_type.unwrap()));
-            },
-            |_| {},
-        )
-        .conditions(
-            name.is_some(),
-            |q| {
-                q.and_where(Expr::col(SourceQueryTable::Name).like(format!("%{}%", name.unwrap())));
-            },
-            |_| {},
-        )
-        .conditions(
-            priority.is_some(),
-            |q| {
-                q.and_where(Expr::col(SourceQueryTable::Priority).eq(priority.unwrap() as u8));
-            },
-            |_| {},
-        )
-        .to_string(MysqlQueryBuilder);
+    let query = build_source_query(filters);
 
     let result = sqlx::query_as(&query)
         .fetch_all(&conn.connection)
@@ -92,24 +65,39 @@ pub async fn source_get(
     Ok(result)
 }
 
-pub async fn source_update(
+pub fn source_get_stream(
     conn: &DatabaseConnection,
+    filters: GetSourcesRequest,
+) -> impl Stream<Item = Result<Source, ApplicationError>> {
+    let query = build_source_query(filters);
+    let pool = conn.connection.clone();
+
+    try_stream! {
+        let mut rows = sqlx::query_as::<_, Source>(&query).fetch(&pool);
+        while let Some(source) = rows.next().await {
+            yield source.map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        }
+    }
+}
+
+pub async fn source_update(
+    tx: &mut Transaction<'_, MySql>,
     source: Source,
 ) -> Result<(), ApplicationError> {
-    sqlx::query("update sources set name = ?, source_type = ?, priority = ? where id = ?")
+    sqlx::query("update sources set name = ?, source_type = ?, priority = ? where id = ? and deleted_at is null")
         .bind(source.name)
         .bind(source.source_type.bits())
         .bind(source.priority)
         .bind(source.id)
-        .execute(&conn.connection)
+        .execute(&mut **tx)
         .await
-        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        .map_err(map_unique_violation(SOURCE_ALREADY_EXISTS))?;
 
     Ok(())
 }
 
 pub async fn source_by_id(conn: &DatabaseConnection, id: u32) -> Result<Source, ApplicationError> {
-    let result = sqlx::query_as("select * from sources where id = ?")
+    let result = sqlx::query_as("select * from sources where id = ? and deleted_at is null")
         .bind(id)
         .fetch_optional(&conn.connection)
         .await
@@ -124,7 +112,7 @@ pub async fn sources_by_season_id(
     season_id: u32,
 ) -> Result<Vec<Source>, ApplicationError> {
     let result = sqlx::query_as(
-        "select s.* from episodes e join sources s on e.source_id = s.id where e.season_id = ? group by s.id, s.name, s.source_type, s.priority",
+        "select s.* from episodes e join sources s on e.source_id = s.id where e.season_id = ? and e.deleted_at is null and s.deleted_at is null group by s.id, s.name, s.source_type, s.priority, s.deleted_at",
     )
     .bind(season_id)
     .fetch_all(&conn.connection)
@@ -133,3 +121,120 @@ pub async fn sources_by_season_id(
 
     Ok(result)
 }
+
+pub async fn source_delete(
+    tx: &mut Transaction<'_, MySql>,
+    source: Source,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update sources set deleted_at = ? where id = ? and deleted_at is null")
+        .bind(source.deleted_at)
+        .bind(source.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    sqlx::query("update episodes set deleted_at = ? where source_id = ? and deleted_at is null")
+        .bind(source.deleted_at)
+        .bind(source.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn source_restore(
+    tx: &mut Transaction<'_, MySql>,
+    id: u32,
+) -> Result<(), ApplicationError> {
+    let deleted_at: DateTime<Utc> = sqlx::query_scalar(
+        "select deleted_at from sources where id = ? and deleted_at is not null",
+    )
+    .bind(id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| ApplicationError::UnknownError(e.into()))?
+    .ok_or(ApplicationError::NotFound)?;
+
+    sqlx::query("update episodes set deleted_at = null where source_id = ? and deleted_at = ?")
+        .bind(id)
+        .bind(deleted_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(map_unique_violation(EPISODE_SEQUENCE_IN_USE))?;
+
+    sqlx::query("update sources set deleted_at = null where id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(map_unique_violation(SOURCE_ALREADY_EXISTS))?;
+
+    Ok(())
+}
+
+pub async fn source_purge(
+    tx: &mut Transaction<'_, MySql>,
+    id: u32,
+) -> Result<(), ApplicationError> {
+    sqlx::query("select id from sources where id = ? and deleted_at is not null for update")
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    sqlx::query("delete from episodes where source_id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    sqlx::query("delete from sources where id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+fn build_source_query(filters: GetSourcesRequest) -> String {
+    let GetSourcesRequest {
+        source_type,
+        name,
+        priority,
+    } = filters;
+
+    Query::select()
+        .columns([
+            SourceQueryTable::Id,
+            SourceQueryTable::Name,
+            SourceQueryTable::SourceType,
+            SourceQueryTable::Priority,
+            SourceQueryTable::DeletedAt,
+        ])
+        .from(SourceQueryTable::Table)
+        .and_where(Expr::col(SourceQueryTable::DeletedAt).is_null())
+        .conditions(
+            source_type.is_some(),
+            move |q| {
+                q.and_where(Expr::col(SourceQueryTable::SourceType).eq(source_type.unwrap()));
+            },
+            |_| {},
+        )
+        .conditions(
+            name.is_some(),
+            |q| {
+                q.and_where(Expr::col(SourceQueryTable::Name).like(format!("%{}%", name.unwrap())));
+            },
+            |_| {},
+        )
+        .conditions(
+            priority.is_some(),
+            |q| {
+                q.and_where(Expr::col(SourceQueryTable::Priority).eq(priority.unwrap() as u8));
+            },
+            |_| {},
+        )
+        .to_string(MysqlQueryBuilder)
+}
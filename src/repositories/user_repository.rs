@@ -1,7 +1,40 @@
+use sea_query::{Asterisk, Expr, Iden, MysqlQueryBuilder, Order, Query};
+use sqlx::{MySql, Transaction};
+use std::fmt::Write;
+
+use crate::arkalis_service::ListUsersRequest;
+use crate::extensions::OptionToAppResult;
+use crate::models::cursor::Cursor;
 use crate::models::error::ApplicationError;
+use crate::models::page;
+use crate::models::page::Page;
+use crate::models::roles::Roles;
 use crate::models::user::User;
+use crate::models::user_cursor::UserCursor;
 use crate::repositories::DatabaseConnection;
 
+enum UserQueryTable {
+    Table,
+    Id,
+    DisplayName,
+    Role,
+    IsBanned,
+}
+
+impl Iden for UserQueryTable {
+    fn unquoted(&self, s: &mut dyn Write) {
+        let name = match self {
+            UserQueryTable::Table => "users",
+            UserQueryTable::Id => "id",
+            UserQueryTable::DisplayName => "display_name",
+            UserQueryTable::Role => "role",
+            UserQueryTable::IsBanned => "is_banned",
+        };
+
+        write!(s, "{}", name).unwrap()
+    }
+}
+
 pub async fn user_add(conn: &DatabaseConnection, user: User) -> Result<(), ApplicationError> {
     sqlx::query("insert into users(id, display_name, role) values (?, ?, ?)")
         .bind(user.id)
@@ -14,12 +47,28 @@ pub async fn user_add(conn: &DatabaseConnection, user: User) -> Result<(), Appli
     Ok(())
 }
 
+pub async fn user_update_profile(
+    conn: &DatabaseConnection,
+    user: User,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update users set mal_profile = ?, anilist_profile = ? where id = ?")
+        .bind(user.mal_profile)
+        .bind(user.anilist_profile)
+        .bind(user.id)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
 pub async fn user_update_recovery_key(
     conn: &DatabaseConnection,
     user: User,
 ) -> Result<(), ApplicationError> {
-    sqlx::query("update users set recovery_key = ? where id = ?")
+    sqlx::query("update users set recovery_key = ?, recovery_key_prefix = ? where id = ?")
         .bind(user.recovery_key)
+        .bind(user.recovery_key_prefix)
         .bind(user.id)
         .execute(&conn.connection)
         .await
@@ -28,12 +77,23 @@ pub async fn user_update_recovery_key(
     Ok(())
 }
 
-pub async fn user_get_by_recovery_key(
+pub async fn user_get_by_recovery_key_prefix(
     conn: &DatabaseConnection,
-    recovery_key: String,
+    prefix: &str,
 ) -> Result<User, ApplicationError> {
-    let result = sqlx::query_as("select * from users where recovery_key = ?")
-        .bind(recovery_key)
+    let result = sqlx::query_as("select * from users where recovery_key_prefix = ?")
+        .bind(prefix)
+        .fetch_optional(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?
+        .ok_or(ApplicationError::NotFound)?;
+
+    Ok(result)
+}
+
+pub async fn user_get_by_id(conn: &DatabaseConnection, id: &str) -> Result<User, ApplicationError> {
+    let result = sqlx::query_as("select * from users where id = ?")
+        .bind(id)
         .fetch_optional(&conn.connection)
         .await
         .map_err(|e| ApplicationError::UnknownError(e.into()))?
@@ -41,3 +101,113 @@ pub async fn user_get_by_recovery_key(
 
     Ok(result)
 }
+
+pub async fn user_update(
+    tx: &mut Transaction<'_, MySql>,
+    user: User,
+) -> Result<(), ApplicationError> {
+    sqlx::query("update users set display_name = ?, role = ?, is_banned = ? where id = ?")
+        .bind(user.display_name)
+        .bind(user.role as u8)
+        .bind(user.is_banned)
+        .bind(user.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn user_search(
+    conn: &DatabaseConnection,
+    filters: ListUsersRequest,
+) -> Result<Page<User>, ApplicationError> {
+    let ListUsersRequest {
+        query,
+        role,
+        is_banned,
+        page_size,
+        cursor,
+    } = filters;
+
+    let role = match role {
+        Some(role) => Some(Roles::parse(&role)? as u8),
+        None => None,
+    };
+    let cursor = if let Some(cursor) = cursor {
+        Some(UserCursor::decode(&cursor)?)
+    } else {
+        None
+    };
+    let page_size = page::page_size(page_size);
+
+    let sql = Query::select()
+        .column(Asterisk)
+        .from(UserQueryTable::Table)
+        .conditions(
+            query.is_some(),
+            |q| {
+                let query = query.unwrap();
+                q.and_where(
+                    Expr::col(UserQueryTable::DisplayName)
+                        .like(format!("%{}%", query))
+                        .or(Expr::col(UserQueryTable::Id).eq(query)),
+                );
+            },
+            |_| {},
+        )
+        .conditions(
+            role.is_some(),
+            |q| {
+                q.and_where(Expr::col(UserQueryTable::Role).eq(role.unwrap()));
+            },
+            |_| {},
+        )
+        .conditions(
+            is_banned.is_some(),
+            |q| {
+                q.and_where(Expr::col(UserQueryTable::IsBanned).eq(is_banned.unwrap()));
+            },
+            |_| {},
+        )
+        .conditions(
+            cursor.is_some(),
+            |q| {
+                let cursor = cursor.unwrap();
+                q.and_where(
+                    Expr::col(UserQueryTable::DisplayName)
+                        .gt(cursor.display_name.clone())
+                        .or(Expr::col(UserQueryTable::DisplayName)
+                            .eq(cursor.display_name)
+                            .and(Expr::col(UserQueryTable::Id).gt(cursor.id))),
+                );
+            },
+            |_| {},
+        )
+        .order_by(UserQueryTable::DisplayName, Order::Asc)
+        .order_by(UserQueryTable::Id, Order::Asc)
+        .limit(page_size + 1)
+        .to_string(MysqlQueryBuilder);
+
+    let mut result: Vec<User> = sqlx::query_as(&sql)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    let next_cursor = if result.len() as u64 > page_size {
+        result.truncate(page_size as usize);
+        let last = result.last().ok_or_app_result("page is empty")?;
+        let cursor = UserCursor {
+            display_name: last.display_name.clone(),
+            id: last.id.clone(),
+        };
+        Some(cursor.encode()?)
+    } else {
+        None
+    };
+
+    Ok(Page {
+        items: result,
+        next_cursor,
+    })
+}
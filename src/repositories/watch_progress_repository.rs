@@ -0,0 +1,56 @@
+use crate::models::error::ApplicationError;
+use crate::models::watch_progress::{ContinueWatchingEntry, WatchProgress};
+use crate::repositories::DatabaseConnection;
+
+pub async fn watch_progress_upsert(
+    conn: &DatabaseConnection,
+    progress: &WatchProgress,
+) -> Result<(), ApplicationError> {
+    sqlx::query("insert into watch_progress (user_id, episode_id, position_seconds, completed, updated_at) values (?, ?, ?, ?, ?) on duplicate key update position_seconds = values(position_seconds), completed = values(completed), updated_at = values(updated_at)")
+        .bind(&progress.user_id)
+        .bind(&progress.episode_id)
+        .bind(progress.position_seconds)
+        .bind(progress.completed)
+        .bind(progress.updated_at)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+pub async fn watch_progress_complete_season(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    season_id: u32,
+    source_id: Option<u32>,
+) -> Result<(), ApplicationError> {
+    sqlx::query("insert into watch_progress (user_id, episode_id, position_seconds, completed, updated_at) select ?, id, 0, true, ? from episodes where season_id = ? and (? is null or source_id = ?) and is_hidden = false and deleted_at is null on duplicate key update completed = true, updated_at = values(updated_at)")
+        .bind(user_id)
+        .bind(chrono::Utc::now())
+        .bind(season_id)
+        .bind(source_id)
+        .bind(source_id)
+        .execute(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(())
+}
+
+const CONTINUE_WATCHING_QUERY: &str = "select * from (select watch_progress.*, episodes.season_id, episodes.source_id, seasons.anime_id, row_number() over (partition by seasons.anime_id order by watch_progress.updated_at desc) as position from watch_progress inner join episodes on episodes.id = watch_progress.episode_id inner join seasons on seasons.id = episodes.season_id inner join animes on animes.id = seasons.anime_id where watch_progress.user_id = ? and episodes.is_hidden = false and episodes.deleted_at is null and seasons.deleted_at is null and animes.is_hidden = false and animes.deleted_at is null) latest where position = 1 order by updated_at desc limit ?";
+
+pub async fn watch_progress_continue_watching(
+    conn: &DatabaseConnection,
+    user_id: &str,
+    limit: u64,
+) -> Result<Vec<ContinueWatchingEntry>, ApplicationError> {
+    let result = sqlx::query_as(CONTINUE_WATCHING_QUERY)
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&conn.connection)
+        .await
+        .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+
+    Ok(result)
+}
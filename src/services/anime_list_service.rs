@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use num_traits::FromPrimitive;
+
+use crate::arkalis_service::{
+    ImportWatchStatusRequest, ImportWatchStatusResponse, ListAnimeListEntriesRequest,
+    ListAnimeListEntriesResponse, RemoveAnimeListEntryRequest, RemoveAnimeListEntryResponse,
+    SetAnimeListEntryRequest, SetAnimeListEntryResponse,
+};
+use crate::models::anime_list_entry::AnimeListEntry;
+use crate::models::error::ApplicationError;
+use crate::models::user::User;
+use crate::models::watch_list_import;
+use crate::models::watch_list_import::ImportFormat;
+use crate::models::watch_status::{status_counts_to_grpc, WatchStatus};
+use crate::repositories::{
+    anime_list_entry_repository, anime_repository, commit, user_repository, DatabaseConnection,
+};
+
+pub struct AnimeListService {
+    pub database_connection: Arc<DatabaseConnection>,
+}
+
+impl AnimeListService {
+    pub async fn import_watch_status(
+        &self,
+        data: ImportWatchStatusRequest,
+        user: &User,
+    ) -> Result<ImportWatchStatusResponse, ApplicationError> {
+        let format = ImportFormat::from_i32(data.format).ok_or(ApplicationError::InvalidData(
+            anyhow::Error::msg("format is invalid"),
+        ))?;
+        let entries = watch_list_import::parse(format, &data.content)?;
+
+        let ids_in_list: Vec<String> = entries
+            .iter()
+            .map(|entry| entry.id_in_list.clone())
+            .collect();
+        let anime_ids_by_entry: HashMap<String, u32> =
+            anime_repository::anime_get_ids_by_list_entries(
+                &self.database_connection,
+                format.anime_list(),
+                &ids_in_list,
+                user.has_uploader_or_adm_role(),
+            )
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut response = ImportWatchStatusResponse::default();
+        let mut list_entries = Vec::new();
+        for entry in entries {
+            let anime_id = anime_ids_by_entry.get(&entry.id_in_list).copied();
+
+            match (entry.status, anime_id) {
+                (Some(status), Some(anime_id)) => {
+                    list_entries.push(AnimeListEntry::new(
+                        user,
+                        anime_id,
+                        status,
+                        entry.score,
+                        entry.progress,
+                    ));
+                    response.matched.push(entry.into_grpc(Some(anime_id)));
+                }
+                _ => response.unknown.push(entry.into_grpc(anime_id)),
+            }
+        }
+
+        // Each upsert locks its anime to update the score, so take the locks in a stable order.
+        list_entries.sort_by_key(|entry| entry.anime_id);
+        let mut tx = self.database_connection.begin().await?;
+        for list_entry in list_entries {
+            anime_list_entry_repository::anime_list_entry_upsert(&mut tx, list_entry, false)
+                .await?;
+            response.imported += 1;
+        }
+        commit(tx).await?;
+
+        Ok(response)
+    }
+
+    pub async fn set_entry(
+        &self,
+        data: SetAnimeListEntryRequest,
+        user: &User,
+    ) -> Result<SetAnimeListEntryResponse, ApplicationError> {
+        anime_repository::anime_get_by_id(
+            &self.database_connection,
+            data.anime_id,
+            user.has_uploader_or_adm_role(),
+        )
+        .await?;
+        let entry = AnimeListEntry::from_grpc(data, user)?;
+        let anime_id = entry.anime_id;
+        let mut tx = self.database_connection.begin().await?;
+        anime_list_entry_repository::anime_list_entry_upsert(&mut tx, entry, true).await?;
+        commit(tx).await?;
+        let entry = anime_list_entry_repository::anime_list_entry_get(
+            &self.database_connection,
+            &user.id,
+            anime_id,
+        )
+        .await?;
+        Ok(SetAnimeListEntryResponse {
+            entry: Some(entry.into()),
+        })
+    }
+
+    pub async fn remove_entry(
+        &self,
+        data: RemoveAnimeListEntryRequest,
+        user: &User,
+    ) -> Result<RemoveAnimeListEntryResponse, ApplicationError> {
+        let mut tx = self.database_connection.begin().await?;
+        anime_list_entry_repository::anime_list_entry_delete(&mut tx, &user.id, data.anime_id)
+            .await?;
+        commit(tx).await?;
+        Ok(RemoveAnimeListEntryResponse {})
+    }
+
+    pub async fn list_entries(
+        &self,
+        data: ListAnimeListEntriesRequest,
+        user: &User,
+    ) -> Result<ListAnimeListEntriesResponse, ApplicationError> {
+        let user_id = match data.user_id {
+            Some(user_id) if user_id != user.id => {
+                user_repository::user_get_by_id(&self.database_connection, &user_id)
+                    .await?
+                    .id
+            }
+            _ => user.id.clone(),
+        };
+        let is_own = user_id == user.id;
+        let show_all = user.has_uploader_or_adm_role();
+        let status = data
+            .status
+            .map(|status| {
+                WatchStatus::from_i32(status).ok_or(ApplicationError::InvalidData(
+                    anyhow::Error::msg("status is invalid"),
+                ))
+            })
+            .transpose()?;
+
+        let page = anime_list_entry_repository::anime_list_entry_list(
+            &self.database_connection,
+            &user_id,
+            status,
+            show_all,
+            data.page_size,
+            data.cursor,
+        )
+        .await?;
+        let counts = anime_list_entry_repository::anime_list_entry_count_by_status(
+            &self.database_connection,
+            &user_id,
+            show_all,
+        )
+        .await?;
+        Ok(ListAnimeListEntriesResponse {
+            entries: page
+                .items
+                .into_iter()
+                .map(|mut entry| {
+                    if !is_own {
+                        entry.notes = None;
+                    }
+                    entry.into()
+                })
+                .collect(),
+            next_cursor: page.next_cursor,
+            counts: status_counts_to_grpc(counts),
+        })
+    }
+}
@@ -1,17 +1,33 @@
+use num_traits::FromPrimitive;
+use serde_json::Value;
 use std::sync::Arc;
+use tokio_stream::{Stream, StreamExt};
 use validator::Validate;
 
+use crate::arkalis_service;
 use crate::arkalis_service::{
-    CreateAnimeRequest, CreateAnimeResponse, EditAnimeRequest, EditAnimeResponse,
-    GetAnimeByIdRequest, GetAnimeByIdResponse, SearchAnimeRequest, SearchAnimeResponse,
+    CreateAnimeRequest, CreateAnimeResponse, DeleteAnimeRequest, DeleteAnimeResponse,
+    EditAnimeRequest, EditAnimeResponse, GetAnimeByExternalIdRequest, GetAnimeByExternalIdResponse,
+    GetAnimeByIdRequest, GetAnimeByIdResponse, GetAnimeRevisionRequest, GetAnimeRevisionResponse,
+    ImportAnimeRequest, ImportAnimeResponse, ListAnimeRevisionsRequest, ListAnimeRevisionsResponse,
+    RevertAnimeRequest, RevertAnimeResponse, SearchAnimeRequest, SearchAnimeResponse,
 };
 use crate::models::anime::Anime;
+use crate::models::anime_in_anime_list::AnimeInAnimeList;
+use crate::models::anime_list::AnimeList;
+use crate::models::anime_metadata::AnimeMetadata;
+use crate::models::anime_revision::AnimeRevision;
+use crate::models::audit_event::{diff, AuditEvent};
+use crate::models::config::Config;
+use crate::models::entity_type::EntityType;
 use crate::models::error::ApplicationError;
+use crate::models::roles::Roles;
 use crate::models::user::User;
-use crate::repositories::anime_repository;
 use crate::repositories::DatabaseConnection;
+use crate::repositories::{anime_repository, anime_revision_repository, audit_repository, commit};
 
 pub struct AnimeService {
+    pub config: Arc<Config>,
     pub database_connection: Arc<DatabaseConnection>,
 }
 
@@ -22,7 +38,21 @@ impl AnimeService {
         user: &User,
     ) -> Result<CreateAnimeResponse, ApplicationError> {
         let anime = Anime::new(data, user)?;
-        let id = anime_repository::anime_add(&self.database_connection, anime).await?;
+        let after = AuditEvent::snapshot(&anime)?;
+        let mut tx = self.database_connection.begin().await?;
+        let id = anime_repository::anime_add(&mut tx, anime).await?;
+        let revision = AnimeRevision::new(id, after.clone(), user, None);
+        anime_revision_repository::anime_revision_add(&mut tx, revision).await?;
+        let event = AuditEvent::new(
+            user,
+            "CreateAnime",
+            EntityType::Anime,
+            id,
+            Value::Null,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
         Ok(CreateAnimeResponse { id })
     }
 
@@ -43,6 +73,29 @@ impl AnimeService {
         Ok(GetAnimeByIdResponse { anime: Some(anime) })
     }
 
+    pub async fn get_anime_by_external_id(
+        &self,
+        data: GetAnimeByExternalIdRequest,
+        user: &Option<User>,
+    ) -> Result<GetAnimeByExternalIdResponse, ApplicationError> {
+        let external_id = AnimeInAnimeList::from_grpc(data.external_id.ok_or(
+            ApplicationError::InvalidData(anyhow::Error::msg("external_id is required")),
+        )?)?;
+        let show_all = if let Some(user) = user {
+            user.has_uploader_or_adm_role()
+        } else {
+            false
+        };
+        let anime = anime_repository::anime_get_by_external_id(
+            &self.database_connection,
+            &external_id,
+            show_all,
+        )
+        .await?
+        .into();
+        Ok(GetAnimeByExternalIdResponse { anime: Some(anime) })
+    }
+
     pub async fn search_anime(
         &self,
         filters: SearchAnimeRequest,
@@ -53,13 +106,33 @@ impl AnimeService {
         } else {
             false
         };
-        let animes =
+        let (page, total) =
             anime_repository::anime_search(&self.database_connection, filters, show_all).await?;
         Ok(SearchAnimeResponse {
-            animes: animes.into_iter().map(|anime| anime.into()).collect(),
+            animes: page.items.into_iter().map(|anime| anime.into()).collect(),
+            next_cursor: page.next_cursor,
+            total,
         })
     }
 
+    pub fn search_anime_stream(
+        &self,
+        filters: SearchAnimeRequest,
+        user: &Option<User>,
+    ) -> Result<
+        impl Stream<Item = Result<arkalis_service::Anime, ApplicationError>>,
+        ApplicationError,
+    > {
+        let show_all = if let Some(user) = user {
+            user.has_uploader_or_adm_role()
+        } else {
+            false
+        };
+        let animes =
+            anime_repository::anime_search_stream(&self.database_connection, filters, show_all)?;
+        Ok(animes.map(|anime| anime.map(|anime| anime.into())))
+    }
+
     pub async fn update_anime(
         &self,
         anime_update: EditAnimeRequest,
@@ -69,9 +142,228 @@ impl AnimeService {
         let anime =
             anime_repository::anime_get_by_id(&self.database_connection, anime_update.id, show_all)
                 .await?;
+        let before = AuditEvent::snapshot(&anime)?;
+        let baseline = AnimeRevision::baseline(&anime)?;
         let anime = anime.update(anime_update, user)?;
         anime.validate()?;
-        anime_repository::anime_update(&self.database_connection, anime).await?;
+        let after = AuditEvent::snapshot(&anime)?;
+        let id = anime.id.unwrap_or(0);
+        let mut tx = self.database_connection.begin().await?;
+        anime_repository::anime_update(&mut tx, anime).await?;
+        if !anime_revision_repository::anime_revision_exists(&mut tx, id).await? {
+            anime_revision_repository::anime_revision_add(&mut tx, baseline).await?;
+        }
+        let revision = AnimeRevision::new(id, after.clone(), user, None);
+        anime_revision_repository::anime_revision_add(&mut tx, revision).await?;
+        let event = AuditEvent::new(user, "EditAnime", EntityType::Anime, id, before, after);
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
         Ok(EditAnimeResponse {})
     }
+
+    pub async fn delete_anime(
+        &self,
+        data: DeleteAnimeRequest,
+        user: &User,
+    ) -> Result<DeleteAnimeResponse, ApplicationError> {
+        let anime =
+            anime_repository::anime_get_by_id(&self.database_connection, data.id, true).await?;
+        let before = AuditEvent::snapshot(&anime)?;
+        let anime = anime.delete(user)?;
+        let after = AuditEvent::snapshot(&anime)?;
+        let mut tx = self.database_connection.begin().await?;
+        anime_repository::anime_delete(&mut tx, anime).await?;
+        let event = AuditEvent::new(
+            user,
+            "DeleteAnime",
+            EntityType::Anime,
+            data.id,
+            before,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+        Ok(DeleteAnimeResponse {})
+    }
+
+    pub async fn list_revisions(
+        &self,
+        data: ListAnimeRevisionsRequest,
+        user: &User,
+    ) -> Result<ListAnimeRevisionsResponse, ApplicationError> {
+        if !user.has_uploader_or_adm_role() {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let revisions = anime_revision_repository::anime_revision_get_by_anime(
+            &self.database_connection,
+            data.anime_id,
+        )
+        .await?
+        .into_iter()
+        .map(arkalis_service::AnimeRevision::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(ListAnimeRevisionsResponse { revisions })
+    }
+
+    pub async fn get_revision(
+        &self,
+        data: GetAnimeRevisionRequest,
+        user: &User,
+    ) -> Result<GetAnimeRevisionResponse, ApplicationError> {
+        if !user.has_uploader_or_adm_role() {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let revision = anime_revision_repository::anime_revision_get(
+            &self.database_connection,
+            data.anime_id,
+            data.revision,
+        )
+        .await?;
+        Ok(GetAnimeRevisionResponse {
+            revision: Some(revision.try_into()?),
+        })
+    }
+
+    pub async fn revert_anime(
+        &self,
+        data: RevertAnimeRequest,
+        user: &User,
+    ) -> Result<RevertAnimeResponse, ApplicationError> {
+        let anime =
+            anime_repository::anime_get_by_id(&self.database_connection, data.anime_id, true)
+                .await?;
+        let revision = anime_revision_repository::anime_revision_get(
+            &self.database_connection,
+            data.anime_id,
+            data.revision,
+        )
+        .await?;
+        let before = AuditEvent::snapshot(&anime)?;
+        let anime = anime.revert(revision.to_anime()?, user)?;
+        anime.validate()?;
+        let after = AuditEvent::snapshot(&anime)?;
+        let mut tx = self.database_connection.begin().await?;
+        anime_repository::anime_update(&mut tx, anime).await?;
+        let revision = AnimeRevision::new(data.anime_id, after.clone(), user, Some(data.revision));
+        let revision = anime_revision_repository::anime_revision_add(&mut tx, revision).await?;
+        let event = AuditEvent::new(
+            user,
+            "RevertAnime",
+            EntityType::Anime,
+            data.anime_id,
+            before,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+        Ok(RevertAnimeResponse { revision })
+    }
+
+    pub async fn import_anime(
+        &self,
+        data: ImportAnimeRequest,
+        user: &User,
+    ) -> Result<ImportAnimeResponse, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let source = data
+            .source
+            .ok_or(ApplicationError::InvalidData(anyhow::Error::msg(
+                "source is required",
+            )))?;
+        let anime_list = AnimeList::from_i32(source.anime_list).ok_or(
+            ApplicationError::InvalidData(anyhow::Error::msg("invalid anime list")),
+        )?;
+        let metadata = AnimeMetadata::fetch(&self.config, anime_list, &source.id_in_list).await?;
+
+        let mut existing_ids = Vec::new();
+        for entry in &metadata.anime_in_lists {
+            let entry = AnimeInAnimeList::new(
+                AnimeList::from_i32(entry.anime_list).unwrap_or(anime_list),
+                entry.id_in_list.clone(),
+            );
+            for id in
+                anime_repository::anime_get_ids_by_list_entry(&self.database_connection, &entry)
+                    .await?
+            {
+                if !existing_ids.contains(&id) {
+                    existing_ids.push(id);
+                }
+            }
+        }
+
+        match existing_ids.as_slice() {
+            [] => {
+                let create_request = metadata.to_create_request();
+                let anime = Anime::new(create_request.clone(), user)?;
+                let changes = diff(
+                    Value::Object(Default::default()),
+                    AuditEvent::snapshot(&anime)?,
+                );
+                let created_id = if data.apply {
+                    Some(self.add_anime(create_request, user).await?.id)
+                } else {
+                    None
+                };
+                Ok(ImportAnimeResponse {
+                    created_id,
+                    existing_id: None,
+                    diff: changes.to_string(),
+                    applied: data.apply,
+                })
+            }
+            [id] => {
+                let anime =
+                    anime_repository::anime_get_by_id(&self.database_connection, *id, true).await?;
+                let before = AuditEvent::snapshot(&anime)?;
+                let edit_request = metadata.to_edit_request(anime.clone().into());
+                let anime = anime.update(edit_request.clone(), user)?;
+                anime.validate()?;
+                let after = AuditEvent::snapshot(&anime)?;
+                let changes = diff(before, after);
+                let applied = data.apply && changes.as_object().is_some_and(|c| !c.is_empty());
+                if applied {
+                    self.update_anime(edit_request, user).await?;
+                }
+                Ok(ImportAnimeResponse {
+                    created_id: None,
+                    existing_id: Some(*id),
+                    diff: changes.to_string(),
+                    applied,
+                })
+            }
+            _ => Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "more than one anime matches this source",
+            ))),
+        }
+    }
+
+    pub async fn report_external_id_conflicts(&self) -> Result<(), ApplicationError> {
+        let conflicts =
+            anime_repository::anime_get_external_id_conflicts(&self.database_connection).await?;
+        for (anime_id, id_in_list, linked_anime_id) in conflicts {
+            log::warn!(
+                "Anime {} lists external id {}, which is linked to anime {}",
+                anime_id,
+                id_in_list,
+                linked_anime_id
+            );
+        }
+
+        Ok(())
+    }
+
+    pub async fn reindex_search(&self) -> Result<(), ApplicationError> {
+        let animes = anime_repository::anime_get_not_indexed(&self.database_connection).await?;
+        for mut anime in animes {
+            anime.reindex_search();
+            anime_repository::anime_update_search(&self.database_connection, anime).await?;
+        }
+
+        Ok(())
+    }
 }
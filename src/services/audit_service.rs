@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use crate::arkalis_service::{ListAuditEventsRequest, ListAuditEventsResponse};
+use crate::models::error::ApplicationError;
+use crate::models::roles::Roles;
+use crate::models::user::User;
+use crate::repositories::{audit_repository, DatabaseConnection};
+
+pub struct AuditService {
+    pub database_connection: Arc<DatabaseConnection>,
+}
+
+impl AuditService {
+    pub async fn list_events(
+        &self,
+        filters: ListAuditEventsRequest,
+        user: &User,
+    ) -> Result<ListAuditEventsResponse, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let page = audit_repository::audit_event_list(&self.database_connection, filters).await?;
+        Ok(ListAuditEventsResponse {
+            events: page.items.into_iter().map(|event| event.into()).collect(),
+            next_cursor: page.next_cursor,
+        })
+    }
+}
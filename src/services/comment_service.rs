@@ -0,0 +1,203 @@
+use num_traits::FromPrimitive;
+use std::sync::Arc;
+
+use crate::arkalis_service::{
+    CreateCommentRequest, CreateCommentResponse, DeleteCommentRequest, DeleteCommentResponse,
+    EditCommentRequest, EditCommentResponse, ListCommentsRequest, ListCommentsResponse,
+    ModerateCommentRequest, ModerateCommentResponse,
+};
+use crate::models::audit_event::AuditEvent;
+use crate::models::comment::Comment;
+use crate::models::comment_moderation_action::CommentModerationAction;
+use crate::models::entity_type::EntityType;
+use crate::models::error::ApplicationError;
+use crate::models::user::User;
+use crate::repositories::comment_repository::CommentFilter;
+use crate::repositories::{
+    anime_repository, audit_repository, comment_repository, commit, episode_repository,
+    DatabaseConnection,
+};
+
+pub struct CommentService {
+    pub database_connection: Arc<DatabaseConnection>,
+}
+
+impl CommentService {
+    pub async fn create_comment(
+        &self,
+        data: CreateCommentRequest,
+        user: &User,
+    ) -> Result<CreateCommentResponse, ApplicationError> {
+        let show_all = user.has_uploader_or_adm_role();
+        let comment = if let Some(parent_id) = data.parent_id {
+            let parent = self.get_visible_parent(parent_id, show_all).await?;
+            let thread = match parent.parent_id {
+                Some(thread_id) => {
+                    comment_repository::comment_get_by_id(&self.database_connection, thread_id)
+                        .await?
+                }
+                None => parent,
+            };
+            Comment::new(data, user, Some(&thread))?
+        } else {
+            self.check_target_visible(data.episode_id.as_deref(), data.anime_id, show_all)
+                .await?;
+            Comment::new(data, user, None)?
+        };
+
+        let id = comment_repository::comment_add(&self.database_connection, comment).await?;
+        Ok(CreateCommentResponse { id })
+    }
+
+    pub async fn edit_comment(
+        &self,
+        data: EditCommentRequest,
+        user: &User,
+    ) -> Result<EditCommentResponse, ApplicationError> {
+        let comment =
+            comment_repository::comment_get_by_id(&self.database_connection, data.id).await?;
+        let comment = comment.edit(data, user)?;
+        let mut tx = self.database_connection.begin().await?;
+        comment_repository::comment_update(&mut tx, comment).await?;
+        commit(tx).await?;
+        Ok(EditCommentResponse {})
+    }
+
+    pub async fn delete_comment(
+        &self,
+        data: DeleteCommentRequest,
+        user: &User,
+    ) -> Result<DeleteCommentResponse, ApplicationError> {
+        let comment =
+            comment_repository::comment_get_by_id(&self.database_connection, data.id).await?;
+        let is_own = comment.user_id == user.id;
+        let before = AuditEvent::snapshot(&comment)?;
+        let comment = comment.delete(user)?;
+        let after = AuditEvent::snapshot(&comment)?;
+        let mut tx = self.database_connection.begin().await?;
+        comment_repository::comment_update(&mut tx, comment).await?;
+        if !is_own {
+            let event = AuditEvent::new(
+                user,
+                "DeleteComment",
+                EntityType::Comment,
+                data.id,
+                before,
+                after,
+            );
+            audit_repository::audit_event_add(&mut tx, event).await?;
+        }
+        commit(tx).await?;
+        Ok(DeleteCommentResponse {})
+    }
+
+    pub async fn moderate_comment(
+        &self,
+        data: ModerateCommentRequest,
+        user: &User,
+    ) -> Result<ModerateCommentResponse, ApplicationError> {
+        let action = CommentModerationAction::from_i32(data.action).ok_or(
+            ApplicationError::InvalidData(anyhow::Error::msg("action is invalid")),
+        )?;
+        let comment =
+            comment_repository::comment_get_by_id(&self.database_connection, data.id).await?;
+        let before = AuditEvent::snapshot(&comment)?;
+        let comment = comment.moderate(action, user)?;
+        let after = AuditEvent::snapshot(&comment)?;
+        let mut tx = self.database_connection.begin().await?;
+        comment_repository::comment_update(&mut tx, comment).await?;
+        let event = AuditEvent::new(
+            user,
+            "ModerateComment",
+            EntityType::Comment,
+            data.id,
+            before,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+        Ok(ModerateCommentResponse {})
+    }
+
+    pub async fn list_comments(
+        &self,
+        data: ListCommentsRequest,
+        user: &Option<User>,
+    ) -> Result<ListCommentsResponse, ApplicationError> {
+        if data.parent_id.is_none() && data.episode_id.is_some() == data.anime_id.is_some() {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "exactly one of episode_id or anime_id is required",
+            )));
+        }
+
+        let show_all = if let Some(user) = user {
+            user.has_uploader_or_adm_role()
+        } else {
+            false
+        };
+        match data.parent_id {
+            Some(parent_id) => {
+                self.get_visible_parent(parent_id, show_all).await?;
+            }
+            None => {
+                self.check_target_visible(data.episode_id.as_deref(), data.anime_id, show_all)
+                    .await?;
+            }
+        }
+        let filter = CommentFilter {
+            episode_id: data.episode_id,
+            anime_id: data.anime_id,
+            parent_id: data.parent_id,
+            page_size: data.page_size,
+            cursor: data.cursor,
+        };
+        let page =
+            comment_repository::comment_list(&self.database_connection, filter, show_all).await?;
+        Ok(ListCommentsResponse {
+            comments: page
+                .items
+                .into_iter()
+                .map(|comment| comment.into())
+                .collect(),
+            next_cursor: page.next_cursor,
+        })
+    }
+
+    async fn get_visible_parent(
+        &self,
+        parent_id: u64,
+        show_all: bool,
+    ) -> Result<Comment, ApplicationError> {
+        let parent =
+            comment_repository::comment_get_by_id(&self.database_connection, parent_id).await?;
+        if parent.is_hidden && !show_all {
+            return Err(ApplicationError::NotFound);
+        }
+        self.check_target_visible(parent.episode_id.as_deref(), parent.anime_id, show_all)
+            .await?;
+
+        Ok(parent)
+    }
+
+    async fn check_target_visible(
+        &self,
+        episode_id: Option<&str>,
+        anime_id: Option<u32>,
+        show_all: bool,
+    ) -> Result<(), ApplicationError> {
+        if let Some(episode_id) = episode_id {
+            episode_repository::episode_get_visible_by_id(
+                &self.database_connection,
+                episode_id,
+                show_all,
+            )
+            .await?;
+        }
+        if let Some(anime_id) = anime_id {
+            anime_repository::anime_get_by_id(&self.database_connection, anime_id, show_all)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
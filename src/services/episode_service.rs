@@ -1,18 +1,27 @@
 use std::sync::Arc;
 
+use serde_json::Value;
+use tokio_stream::{Stream, StreamExt};
 use validator::Validate;
 
+use crate::arkalis_service;
 use crate::arkalis_service::{
-    CreateEpisodeRequest, CreateEpisodeResponse, GetEpisodeByIdRequest, GetEpisodeByIdResponse,
-    GetEpisodesBySeasonAndSourceRequest, GetEpisodesBySeasonAndSourceResponse,
-    UpdateEpisodeRequest, UpdateEpisodeResponse,
+    CreateEpisodeRequest, CreateEpisodeResponse, DeleteEpisodeRequest, DeleteEpisodeResponse,
+    GetEpisodeByIdRequest, GetEpisodeByIdResponse, GetEpisodesBySeasonAndSourceRequest,
+    GetEpisodesBySeasonAndSourceResponse, UpdateEpisodeRequest, UpdateEpisodeResponse,
 };
+use crate::models::audit_event::AuditEvent;
+use crate::models::config::Config;
+use crate::models::entity_type::EntityType;
 use crate::models::episode::Episode;
 use crate::models::error::ApplicationError;
 use crate::models::user::User;
-use crate::repositories::{episode_repository, DatabaseConnection};
+use crate::repositories::{
+    audit_repository, commit, episode_repository, notification_repository, DatabaseConnection,
+};
 
 pub struct EpisodeService {
+    pub config: Arc<Config>,
     pub database_connection: Arc<DatabaseConnection>,
 }
 
@@ -27,8 +36,25 @@ impl EpisodeService {
 
         let id = episode.id.clone();
         let name = episode.name.clone();
+        let published = episode.is_playable();
+        let season_id = episode.season_id;
+        let after = AuditEvent::snapshot(&episode)?;
 
-        episode_repository::episode_add(&self.database_connection, episode).await?;
+        let mut tx = self.database_connection.begin().await?;
+        episode_repository::episode_add(&mut tx, episode).await?;
+        if published {
+            notification_repository::notification_add_for_episode(&mut tx, &id, season_id).await?;
+        }
+        let event = AuditEvent::new(
+            user,
+            "CreateEpisode",
+            EntityType::Episode,
+            &id,
+            Value::Null,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
 
         Ok(CreateEpisodeResponse { id, name })
     }
@@ -40,8 +66,29 @@ impl EpisodeService {
     ) -> Result<UpdateEpisodeResponse, ApplicationError> {
         let episode =
             episode_repository::episode_get_by_id(&self.database_connection, &data.id).await?;
-        let episode = episode.update_episode(data, user).await?;
-        episode_repository::episode_update(&self.database_connection, episode).await?;
+        let before = AuditEvent::snapshot(&episode)?;
+        let was_playable = episode.is_playable();
+        let id = data.id.clone();
+        let episode = episode.update_episode(data, user, &self.config).await?;
+        episode.validate()?;
+        let after = AuditEvent::snapshot(&episode)?;
+        let published = !was_playable && episode.is_playable();
+        let season_id = episode.season_id;
+        let mut tx = self.database_connection.begin().await?;
+        episode_repository::episode_update(&mut tx, episode).await?;
+        if published {
+            notification_repository::notification_add_for_episode(&mut tx, &id, season_id).await?;
+        }
+        let event = AuditEvent::new(
+            user,
+            "UpdateEpisode",
+            EntityType::Episode,
+            id,
+            before,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
 
         Ok(UpdateEpisodeResponse {})
     }
@@ -62,6 +109,18 @@ impl EpisodeService {
         })
     }
 
+    pub fn get_episodes_by_season_and_source_stream(
+        &self,
+        filter: GetEpisodesBySeasonAndSourceRequest,
+    ) -> impl Stream<Item = Result<arkalis_service::Episode, ApplicationError>> {
+        episode_repository::episode_get_by_season_and_source_stream(
+            &self.database_connection,
+            filter.season_id,
+            filter.source_id,
+        )
+        .map(|episode| episode?.parse_to_grpc_model())
+    }
+
     pub async fn get_episode_bu_id(
         &self,
         filter: GetEpisodeByIdRequest,
@@ -73,4 +132,29 @@ impl EpisodeService {
             episode: Some(episode_grpc),
         })
     }
+
+    pub async fn delete_episode(
+        &self,
+        data: DeleteEpisodeRequest,
+        user: &User,
+    ) -> Result<DeleteEpisodeResponse, ApplicationError> {
+        let episode =
+            episode_repository::episode_get_by_id(&self.database_connection, &data.id).await?;
+        let before = AuditEvent::snapshot(&episode)?;
+        let episode = episode.delete(user)?;
+        let after = AuditEvent::snapshot(&episode)?;
+        let mut tx = self.database_connection.begin().await?;
+        episode_repository::episode_delete(&mut tx, episode).await?;
+        let event = AuditEvent::new(
+            user,
+            "DeleteEpisode",
+            EntityType::Episode,
+            data.id,
+            before,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+        Ok(DeleteEpisodeResponse {})
+    }
 }
@@ -0,0 +1,176 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::arkalis_service::{ListBrokenEpisodesRequest, ListBrokenEpisodesResponse};
+use crate::media_providers;
+use crate::models::config::Config;
+use crate::models::episode::Episode;
+use crate::models::episode_health_check::EpisodeHealthCheck;
+use crate::models::error::ApplicationError;
+use crate::models::roles::Roles;
+use crate::models::user::User;
+use crate::repositories::{episode_health_repository, episode_repository, DatabaseConnection};
+
+const DEFAULT_CHECK_INTERVAL_SECONDS: u64 = 6 * 60 * 60;
+const DEFAULT_BATCH_SIZE: u32 = 50;
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+#[derive(Clone)]
+pub struct MediaHealthService {
+    pub config: Arc<Config>,
+    pub database_connection: Arc<DatabaseConnection>,
+}
+
+impl MediaHealthService {
+    pub fn start(&self) {
+        let service = self.clone();
+        tokio::spawn(async move { service.run().await });
+    }
+
+    async fn run(self) {
+        let mut interval = tokio::time::interval(self.check_interval());
+        loop {
+            interval.tick().await;
+            // Each round runs as its own task, so a panic is logged instead of ending the loop.
+            let service = self.clone();
+            match tokio::spawn(async move { service.check_due_episodes().await }).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => log::error!("Media health check failed: {:?}", e),
+                Err(e) => log::error!("Media health check panicked: {:?}", e),
+            }
+        }
+    }
+
+    fn check_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.config
+                .media_health_check_interval_seconds
+                .unwrap_or(DEFAULT_CHECK_INTERVAL_SECONDS),
+        )
+    }
+
+    async fn check_due_episodes(&self) -> Result<(), ApplicationError> {
+        let checked_before = Utc::now()
+            - chrono::Duration::from_std(self.check_interval())
+                .map_err(|e| ApplicationError::UnknownError(e.into()))?;
+        let batch_size = self
+            .config
+            .media_health_check_batch_size
+            .unwrap_or(DEFAULT_BATCH_SIZE);
+
+        loop {
+            let episodes = episode_health_repository::episode_get_due_for_check(
+                &self.database_connection,
+                checked_before,
+                batch_size,
+            )
+            .await?;
+            let is_last_batch = (episodes.len() as u32) < batch_size;
+            for episode in episodes {
+                self.check_episode(episode).await?;
+            }
+
+            if is_last_batch {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn check_episode(&self, mut episode: Episode) -> Result<(), ApplicationError> {
+        let Some(media_id) = episode.media_id.as_deref() else {
+            return Ok(());
+        };
+        let previous = episode_health_repository::episode_health_check_get(
+            &self.database_connection,
+            &episode.id,
+        )
+        .await?;
+        let provider = media_providers::media_provider(episode.media_provider, &self.config);
+
+        let check = match provider.resolve(media_id).await {
+            Ok(media) => {
+                // Also backfills metadata for episodes saved before it was stored.
+                episode.set_media_metadata(&media);
+                if let Some(content_url) = media.content_url {
+                    episode.file_name = Some(content_url);
+                }
+                let updated =
+                    episode_repository::episode_update_media(&self.database_connection, &episode)
+                        .await?;
+                if !updated {
+                    // The media was changed or the episode deleted while it was being checked.
+                    return Ok(());
+                }
+
+                if episode.is_hidden
+                    && previous.as_ref().is_some_and(|check| check.auto_hidden)
+                    && episode_repository::episode_set_hidden(
+                        &self.database_connection,
+                        &episode,
+                        false,
+                    )
+                    .await?
+                {
+                    log::info!("Episode {} is reachable again, unhiding it", episode.id);
+                }
+
+                EpisodeHealthCheck::healthy(episode.id)
+            }
+            Err(e) => {
+                let mut check =
+                    EpisodeHealthCheck::failed(episode.id.clone(), previous.as_ref(), &e);
+                let threshold = self
+                    .config
+                    .media_health_failure_threshold
+                    .unwrap_or(DEFAULT_FAILURE_THRESHOLD);
+                if !episode.is_hidden
+                    && check.consecutive_failures >= threshold
+                    && episode_repository::episode_set_hidden(
+                        &self.database_connection,
+                        &episode,
+                        true,
+                    )
+                    .await?
+                {
+                    check.auto_hidden = true;
+                    log::warn!(
+                        "Episode {} failed {} health checks, hiding it",
+                        check.episode_id,
+                        check.consecutive_failures
+                    );
+                }
+
+                check
+            }
+        };
+
+        episode_health_repository::episode_health_check_save(&self.database_connection, check).await
+    }
+
+    pub async fn list_broken_episodes(
+        &self,
+        data: ListBrokenEpisodesRequest,
+        user: &User,
+    ) -> Result<ListBrokenEpisodesResponse, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let page = episode_health_repository::broken_episode_list(
+            &self.database_connection,
+            data.page_size,
+            data.cursor,
+        )
+        .await?;
+        Ok(ListBrokenEpisodesResponse {
+            episodes: page
+                .items
+                .into_iter()
+                .map(|episode| episode.into())
+                .collect(),
+            next_cursor: page.next_cursor,
+        })
+    }
+}
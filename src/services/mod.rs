@@ -1,5 +1,13 @@
+pub mod anime_list_service;
 pub mod anime_service;
+pub mod audit_service;
+pub mod comment_service;
 pub mod episode_service;
+pub mod media_health_service;
+pub mod notification_service;
+pub mod rating_service;
 pub mod season_service;
 pub mod source_service;
+pub mod trash_service;
 pub mod user_service;
+pub mod watch_progress_service;
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use crate::arkalis_service::{
+    ClearNotificationsRequest, ClearNotificationsResponse, FollowAnimeRequest, FollowAnimeResponse,
+    ListFollowedAnimesRequest, ListFollowedAnimesResponse, ListNotificationsRequest,
+    ListNotificationsResponse, MarkNotificationsReadRequest, MarkNotificationsReadResponse,
+    UnfollowAnimeRequest, UnfollowAnimeResponse,
+};
+use crate::models::error::ApplicationError;
+use crate::models::user::User;
+use crate::repositories::{anime_repository, notification_repository, DatabaseConnection};
+
+pub struct NotificationService {
+    pub database_connection: Arc<DatabaseConnection>,
+}
+
+impl NotificationService {
+    pub async fn follow_anime(
+        &self,
+        data: FollowAnimeRequest,
+        user: &User,
+    ) -> Result<FollowAnimeResponse, ApplicationError> {
+        anime_repository::anime_get_by_id(
+            &self.database_connection,
+            data.anime_id,
+            user.has_uploader_or_adm_role(),
+        )
+        .await?;
+        notification_repository::follow_add(&self.database_connection, &user.id, data.anime_id)
+            .await?;
+        Ok(FollowAnimeResponse {})
+    }
+
+    pub async fn unfollow_anime(
+        &self,
+        data: UnfollowAnimeRequest,
+        user: &User,
+    ) -> Result<UnfollowAnimeResponse, ApplicationError> {
+        notification_repository::follow_delete(&self.database_connection, &user.id, data.anime_id)
+            .await?;
+        Ok(UnfollowAnimeResponse {})
+    }
+
+    pub async fn list_followed(
+        &self,
+        data: ListFollowedAnimesRequest,
+        user: &User,
+    ) -> Result<ListFollowedAnimesResponse, ApplicationError> {
+        let page = notification_repository::follow_list(
+            &self.database_connection,
+            &user.id,
+            data.page_size,
+            data.cursor,
+        )
+        .await?;
+        Ok(ListFollowedAnimesResponse {
+            anime_ids: page.items,
+            next_cursor: page.next_cursor,
+        })
+    }
+
+    pub async fn list_notifications(
+        &self,
+        data: ListNotificationsRequest,
+        user: &User,
+    ) -> Result<ListNotificationsResponse, ApplicationError> {
+        let page = notification_repository::notification_list(
+            &self.database_connection,
+            &user.id,
+            data.unread_only,
+            data.page_size,
+            data.cursor,
+        )
+        .await?;
+        let unread_count =
+            notification_repository::notification_count_unread(&self.database_connection, &user.id)
+                .await?;
+        Ok(ListNotificationsResponse {
+            notifications: page
+                .items
+                .into_iter()
+                .map(|notification| notification.into())
+                .collect(),
+            next_cursor: page.next_cursor,
+            unread_count,
+        })
+    }
+
+    pub async fn mark_read(
+        &self,
+        data: MarkNotificationsReadRequest,
+        user: &User,
+    ) -> Result<MarkNotificationsReadResponse, ApplicationError> {
+        let ids = if data.all {
+            None
+        } else if data.ids.is_empty() {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "ids is required unless all is set",
+            )));
+        } else {
+            Some(data.ids)
+        };
+        notification_repository::notification_mark_read(&self.database_connection, &user.id, ids)
+            .await?;
+        Ok(MarkNotificationsReadResponse {})
+    }
+
+    pub async fn clear(
+        &self,
+        data: ClearNotificationsRequest,
+        user: &User,
+    ) -> Result<ClearNotificationsResponse, ApplicationError> {
+        notification_repository::notification_clear(
+            &self.database_connection,
+            &user.id,
+            data.read_only,
+        )
+        .await?;
+        Ok(ClearNotificationsResponse {})
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use crate::arkalis_service::{
+    DeleteAnimeRatingRequest, DeleteAnimeRatingResponse, GetAnimeRatingRequest,
+    GetAnimeRatingResponse, RateAnimeRequest, RateAnimeResponse,
+};
+use crate::models::anime_rating::AnimeRating;
+use crate::models::error::ApplicationError;
+use crate::models::user::User;
+use crate::repositories::{anime_rating_repository, anime_repository, commit, DatabaseConnection};
+
+pub struct RatingService {
+    pub database_connection: Arc<DatabaseConnection>,
+}
+
+impl RatingService {
+    pub async fn rate_anime(
+        &self,
+        data: RateAnimeRequest,
+        user: &User,
+    ) -> Result<RateAnimeResponse, ApplicationError> {
+        anime_repository::anime_get_by_id(
+            &self.database_connection,
+            data.anime_id,
+            user.has_uploader_or_adm_role(),
+        )
+        .await?;
+        let rating = AnimeRating::new(user, data.anime_id, data.score)?;
+        let mut tx = self.database_connection.begin().await?;
+        anime_rating_repository::anime_rating_set(&mut tx, &rating).await?;
+        commit(tx).await?;
+        let anime =
+            anime_repository::anime_get_by_id(&self.database_connection, data.anime_id, true)
+                .await?;
+        Ok(RateAnimeResponse {
+            average_score: anime.average_score,
+            vote_count: anime.vote_count,
+        })
+    }
+
+    pub async fn delete_rating(
+        &self,
+        data: DeleteAnimeRatingRequest,
+        user: &User,
+    ) -> Result<DeleteAnimeRatingResponse, ApplicationError> {
+        let mut tx = self.database_connection.begin().await?;
+        anime_rating_repository::anime_rating_delete(&mut tx, &user.id, data.anime_id).await?;
+        commit(tx).await?;
+        Ok(DeleteAnimeRatingResponse {})
+    }
+
+    pub async fn get_rating(
+        &self,
+        data: GetAnimeRatingRequest,
+        user: &User,
+    ) -> Result<GetAnimeRatingResponse, ApplicationError> {
+        let score = anime_rating_repository::anime_rating_get(
+            &self.database_connection,
+            &user.id,
+            data.anime_id,
+        )
+        .await?;
+        Ok(GetAnimeRatingResponse {
+            score: score.map(u32::from),
+        })
+    }
+}
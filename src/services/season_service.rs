@@ -1,17 +1,22 @@
 use std::sync::Arc;
 
+use serde_json::Value;
+use tokio_stream::{Stream, StreamExt};
 use validator::Validate;
 
+use crate::arkalis_service;
 use crate::arkalis_service::{
-    AddSeasonRequest, AddSeasonResponse, EditSeasonRequest, EditSeasonResponse,
-    GetAnimeSeasonsRequest, GetAnimeSeasonsResponse, GetLastSeasonSequenceRequest,
-    GetLastSeasonSequenceResponse,
+    AddSeasonRequest, AddSeasonResponse, DeleteSeasonRequest, DeleteSeasonResponse,
+    EditSeasonRequest, EditSeasonResponse, GetAnimeSeasonsRequest, GetAnimeSeasonsResponse,
+    GetLastSeasonSequenceRequest, GetLastSeasonSequenceResponse,
 };
+use crate::models::audit_event::AuditEvent;
+use crate::models::entity_type::EntityType;
 use crate::models::error::ApplicationError;
 use crate::models::season::Season;
 use crate::models::user::User;
-use crate::repositories::season_repository;
 use crate::repositories::DatabaseConnection;
+use crate::repositories::{audit_repository, commit, season_repository};
 
 pub struct SeasonService {
     pub database_connection: Arc<DatabaseConnection>,
@@ -24,7 +29,19 @@ impl SeasonService {
         user: &User,
     ) -> Result<AddSeasonResponse, ApplicationError> {
         let season = Season::new(data, user)?;
-        let id = season_repository::season_add(&self.database_connection, season).await?;
+        let after = AuditEvent::snapshot(&season)?;
+        let mut tx = self.database_connection.begin().await?;
+        let id = season_repository::season_add(&mut tx, season).await?;
+        let event = AuditEvent::new(
+            user,
+            "AddSeason",
+            EntityType::Season,
+            id,
+            Value::Null,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
         Ok(AddSeasonResponse { id })
     }
 
@@ -57,6 +74,14 @@ impl SeasonService {
         })
     }
 
+    pub fn get_by_anime_stream(
+        &self,
+        anime: GetAnimeSeasonsRequest,
+    ) -> impl Stream<Item = Result<arkalis_service::Season, ApplicationError>> {
+        season_repository::season_get_by_anime_stream(&self.database_connection, anime.anime_id)
+            .map(|season| season.map(|s| s.into()))
+    }
+
     pub async fn update_season(
         &self,
         update_data: EditSeasonRequest,
@@ -64,9 +89,40 @@ impl SeasonService {
     ) -> Result<EditSeasonResponse, ApplicationError> {
         let season =
             season_repository::season_bu_id(&self.database_connection, update_data.id).await?;
+        let before = AuditEvent::snapshot(&season)?;
+        let id = update_data.id;
         let season = season.edit(update_data, user)?;
         season.validate()?;
-        season_repository::season_update(&self.database_connection, season).await?;
+        let after = AuditEvent::snapshot(&season)?;
+        let mut tx = self.database_connection.begin().await?;
+        season_repository::season_update(&mut tx, season).await?;
+        let event = AuditEvent::new(user, "EditSeason", EntityType::Season, id, before, after);
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
         Ok(EditSeasonResponse {})
     }
+
+    pub async fn delete_season(
+        &self,
+        data: DeleteSeasonRequest,
+        user: &User,
+    ) -> Result<DeleteSeasonResponse, ApplicationError> {
+        let season = season_repository::season_bu_id(&self.database_connection, data.id).await?;
+        let before = AuditEvent::snapshot(&season)?;
+        let season = season.delete(user)?;
+        let after = AuditEvent::snapshot(&season)?;
+        let mut tx = self.database_connection.begin().await?;
+        season_repository::season_delete(&mut tx, season).await?;
+        let event = AuditEvent::new(
+            user,
+            "DeleteSeason",
+            EntityType::Season,
+            data.id,
+            before,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+        Ok(DeleteSeasonResponse {})
+    }
 }
@@ -1,13 +1,18 @@
 use crate::arkalis_service::{
-    CreateSourceRequest, CreateSourceResponse, EditSourceRequest, EditSourceResponse,
-    GetSourceByIdRequest, GetSourceByIdResponse, GetSourcesBySeasonIdRequest,
-    GetSourcesBySeasonIdResponse, GetSourcesRequest, GetSourcesResponse, Sources,
+    CreateSourceRequest, CreateSourceResponse, DeleteSourceRequest, DeleteSourceResponse,
+    EditSourceRequest, EditSourceResponse, GetSourceByIdRequest, GetSourceByIdResponse,
+    GetSourcesBySeasonIdRequest, GetSourcesBySeasonIdResponse, GetSourcesRequest,
+    GetSourcesResponse, Sources,
 };
+use crate::models::audit_event::AuditEvent;
+use crate::models::entity_type::EntityType;
 use crate::models::error::ApplicationError;
 use crate::models::source::Source;
 use crate::models::user::User;
-use crate::repositories::{source_repository, DatabaseConnection};
+use crate::repositories::{audit_repository, commit, source_repository, DatabaseConnection};
+use serde_json::Value;
 use std::sync::Arc;
+use tokio_stream::{Stream, StreamExt};
 use validator::Validate;
 
 pub struct SourceService {
@@ -22,7 +27,19 @@ impl SourceService {
     ) -> Result<CreateSourceResponse, ApplicationError> {
         let source = Source::new(data, user)?;
         source.validate()?;
-        let id = source_repository::source_add(&self.database_connection, source).await?;
+        let after = AuditEvent::snapshot(&source)?;
+        let mut tx = self.database_connection.begin().await?;
+        let id = source_repository::source_add(&mut tx, source).await?;
+        let event = AuditEvent::new(
+            user,
+            "CreateSource",
+            EntityType::Source,
+            id,
+            Value::Null,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
         Ok(CreateSourceResponse { id })
     }
 
@@ -35,6 +52,14 @@ impl SourceService {
         Ok(GetSourcesResponse { sources: items })
     }
 
+    pub fn get_sources_stream(
+        &self,
+        filters: GetSourcesRequest,
+    ) -> impl Stream<Item = Result<Sources, ApplicationError>> {
+        source_repository::source_get_stream(&self.database_connection, filters)
+            .map(|source| source.map(Sources::from))
+    }
+
     pub async fn update_source(
         &self,
         edit_data: EditSourceRequest,
@@ -42,9 +67,16 @@ impl SourceService {
     ) -> Result<EditSourceResponse, ApplicationError> {
         let source =
             source_repository::source_by_id(&self.database_connection, edit_data.id).await?;
+        let before = AuditEvent::snapshot(&source)?;
+        let id = edit_data.id;
         let source = source.edit(edit_data, user)?;
         source.validate()?;
-        source_repository::source_update(&self.database_connection, source).await?;
+        let after = AuditEvent::snapshot(&source)?;
+        let mut tx = self.database_connection.begin().await?;
+        source_repository::source_update(&mut tx, source).await?;
+        let event = AuditEvent::new(user, "EditSource", EntityType::Source, id, before, after);
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
         Ok(EditSourceResponse {})
     }
 
@@ -70,4 +102,28 @@ impl SourceService {
             sources: sources.into_iter().map(Sources::from).collect(),
         })
     }
+
+    pub async fn delete_source(
+        &self,
+        data: DeleteSourceRequest,
+        user: &User,
+    ) -> Result<DeleteSourceResponse, ApplicationError> {
+        let source = source_repository::source_by_id(&self.database_connection, data.id).await?;
+        let before = AuditEvent::snapshot(&source)?;
+        let source = source.delete(user)?;
+        let after = AuditEvent::snapshot(&source)?;
+        let mut tx = self.database_connection.begin().await?;
+        source_repository::source_delete(&mut tx, source).await?;
+        let event = AuditEvent::new(
+            user,
+            "DeleteSource",
+            EntityType::Source,
+            data.id,
+            before,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+        Ok(DeleteSourceResponse {})
+    }
 }
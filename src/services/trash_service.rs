@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use num_traits::FromPrimitive;
+use serde_json::Value;
+
+use crate::arkalis_service::{
+    PurgeEntityRequest, PurgeEntityResponse, RestoreEntityRequest, RestoreEntityResponse,
+};
+use crate::models::audit_event::AuditEvent;
+use crate::models::entity_type::EntityType;
+use crate::models::error::ApplicationError;
+use crate::models::roles::Roles;
+use crate::models::user::User;
+use crate::repositories::{
+    anime_repository, audit_repository, commit, episode_repository, season_repository,
+    source_repository, DatabaseConnection,
+};
+
+pub struct TrashService {
+    pub database_connection: Arc<DatabaseConnection>,
+}
+
+impl TrashService {
+    pub async fn restore(
+        &self,
+        data: RestoreEntityRequest,
+        user: &User,
+    ) -> Result<RestoreEntityResponse, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let mut tx = self.database_connection.begin().await?;
+        let entity_type = parse_entity_type(data.entity_type)?;
+        match entity_type {
+            EntityType::Anime => {
+                anime_repository::anime_restore(&mut tx, parse_id(&data.id)?).await?
+            }
+            EntityType::Season => {
+                season_repository::season_restore(&mut tx, parse_id(&data.id)?).await?
+            }
+            EntityType::Source => {
+                source_repository::source_restore(&mut tx, parse_id(&data.id)?).await?
+            }
+            EntityType::Episode => episode_repository::episode_restore(&mut tx, &data.id).await?,
+            EntityType::User | EntityType::Comment => return Err(not_trashable()),
+        }
+
+        let event = AuditEvent::new(
+            user,
+            "RestoreEntity",
+            entity_type,
+            data.id,
+            Value::Null,
+            Value::Null,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+
+        Ok(RestoreEntityResponse {})
+    }
+
+    pub async fn purge(
+        &self,
+        data: PurgeEntityRequest,
+        user: &User,
+    ) -> Result<PurgeEntityResponse, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let mut tx = self.database_connection.begin().await?;
+        let entity_type = parse_entity_type(data.entity_type)?;
+        match entity_type {
+            EntityType::Anime => {
+                anime_repository::anime_purge(&mut tx, parse_id(&data.id)?).await?
+            }
+            EntityType::Season => {
+                season_repository::season_purge(&mut tx, parse_id(&data.id)?).await?
+            }
+            EntityType::Source => {
+                source_repository::source_purge(&mut tx, parse_id(&data.id)?).await?
+            }
+            EntityType::Episode => episode_repository::episode_purge(&mut tx, &data.id).await?,
+            EntityType::User | EntityType::Comment => return Err(not_trashable()),
+        }
+
+        let event = AuditEvent::new(
+            user,
+            "PurgeEntity",
+            entity_type,
+            data.id,
+            Value::Null,
+            Value::Null,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+
+        Ok(PurgeEntityResponse {})
+    }
+}
+
+fn parse_entity_type(entity_type: i32) -> Result<EntityType, ApplicationError> {
+    EntityType::from_i32(entity_type).ok_or(ApplicationError::InvalidData(anyhow::Error::msg(
+        "entity_type is invalid",
+    )))
+}
+
+fn parse_id(id: &str) -> Result<u32, ApplicationError> {
+    id.parse()
+        .map_err(|_| ApplicationError::InvalidData(anyhow::Error::msg("id is not a valid number")))
+}
+
+fn not_trashable() -> ApplicationError {
+    ApplicationError::InvalidData(anyhow::Error::msg(
+        "entity_type cannot be restored or purged",
+    ))
+}
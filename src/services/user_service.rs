@@ -1,21 +1,59 @@
+use chrono::Utc;
+use sqlx::{MySql, Transaction};
 use std::sync::Arc;
 use validator::Validate;
 
 use crate::arkalis_service::{
-    CreateAdminRequest, CreateAdminResponse, CreateRecoveryKeyResponse, CreateTokenRequest,
-    CreateTokenResponse, RecoveryUserRequest, RecoveryUserResponse,
+    ChangePasswordRequest, ChangePasswordResponse, CreateAdminRequest, CreateAdminResponse,
+    CreateRecoveryKeyResponse, CreateTokenRequest, CreateTokenResponse, ExchangeLegacyTokenRequest,
+    ExchangeLegacyTokenResponse, GetUserInfoResponse, LinkCredentialsRequest,
+    LinkCredentialsResponse, ListSessionsRequest, ListSessionsResponse, ListUsersRequest,
+    ListUsersResponse, LoginRequest, LoginResponse, RecoveryUserRequest, RecoveryUserResponse,
+    RefreshTokenRequest, RefreshTokenResponse, RenameUserRequest, RenameUserResponse,
+    RevokeAllSessionsRequest, RevokeAllSessionsResponse, RevokeSessionRequest,
+    RevokeSessionResponse, RotateRecoveryKeyRequest, RotateRecoveryKeyResponse, SetUserBanRequest,
+    SetUserBanResponse, SetUserRoleRequest, SetUserRoleResponse, UpdateProfileRequest,
+    UpdateProfileResponse,
 };
+use crate::extensions::{AuthenticationCache, RateLimiter};
+use crate::models::audit_event::AuditEvent;
 use crate::models::config::Config;
+use crate::models::credentials::{normalize_email, Credentials};
+use crate::models::entity_type::EntityType;
 use crate::models::error::ApplicationError;
+use crate::models::refresh_token::RefreshToken;
+use crate::models::roles::Roles;
+use crate::models::secret_hash;
+use crate::models::session::Session;
 use crate::models::user::User;
-use crate::repositories::{user_repository, DatabaseConnection};
+use crate::models::watch_status::status_counts_to_grpc;
+use crate::repositories::{
+    anime_list_entry_repository, audit_repository, commit, credentials_repository,
+    refresh_token_repository, session_repository, user_repository, DatabaseConnection,
+};
 
 pub struct UserService {
     pub config: Arc<Config>,
     pub database_connection: Arc<DatabaseConnection>,
+    pub authentication_cache: Arc<AuthenticationCache>,
+    pub client_rate_limiter: RateLimiter,
+    pub recovery_rate_limiter: RateLimiter,
+    pub login_rate_limiter: RateLimiter,
 }
 
 impl UserService {
+    pub async fn get_user_info(&self, user: User) -> Result<GetUserInfoResponse, ApplicationError> {
+        let counts = anime_list_entry_repository::anime_list_entry_count_by_status(
+            &self.database_connection,
+            &user.id,
+            user.has_uploader_or_adm_role(),
+        )
+        .await?;
+        let mut response: GetUserInfoResponse = user.into();
+        response.list_counts = status_counts_to_grpc(counts);
+        Ok(response)
+    }
+
     pub async fn generate_token(
         &self,
         data: CreateTokenRequest,
@@ -23,8 +61,11 @@ impl UserService {
         let user = User::from(data);
         user.validate()?;
         user_repository::user_add(&self.database_connection, user.clone()).await?;
-        let token = user.generate_token(&self.config)?;
-        Ok(CreateTokenResponse { token })
+        let (token, refresh_token) = self.create_session_token(user).await?;
+        Ok(CreateTokenResponse {
+            token,
+            refresh_token,
+        })
     }
 
     pub async fn create_adm_token(
@@ -34,32 +75,418 @@ impl UserService {
         let user = User::create_adm_user(&self.config, data.display_name, &data.admin_master_key)?;
         user.validate()?;
         user_repository::user_add(&self.database_connection, user.clone()).await?;
-        let token = user.generate_token(&self.config)?;
+        let (token, refresh_token) = self.create_session_token(user).await?;
 
-        Ok(CreateAdminResponse { token })
+        Ok(CreateAdminResponse {
+            token,
+            refresh_token,
+        })
     }
 
     pub async fn get_recovery_key(
         &self,
         mut user: User,
     ) -> Result<CreateRecoveryKeyResponse, ApplicationError> {
-        let mnemonic = user.get_recovery_mnemonic();
+        if user.recovery_key.is_some() {
+            return Err(ApplicationError::InvalidData(anyhow::Error::msg(
+                "recovery key already exists, rotate it instead",
+            )));
+        }
+
+        let recovery_key = user.generate_recovery_key().await?;
         user_repository::user_update_recovery_key(&self.database_connection, user).await?;
-        Ok(CreateRecoveryKeyResponse {
-            recovery_key: mnemonic,
-        })
+        Ok(CreateRecoveryKeyResponse { recovery_key })
+    }
+
+    pub async fn rotate_recovery_key(
+        &self,
+        _: RotateRecoveryKeyRequest,
+        user: &User,
+    ) -> Result<RotateRecoveryKeyResponse, ApplicationError> {
+        let mut user = user.clone();
+        let recovery_key = user.generate_recovery_key().await?;
+        user_repository::user_update_recovery_key(&self.database_connection, user).await?;
+        Ok(RotateRecoveryKeyResponse { recovery_key })
     }
 
     pub async fn recovery_user(
         &self,
         recovery_data: RecoveryUserRequest,
+        client: &str,
     ) -> Result<RecoveryUserResponse, ApplicationError> {
-        let user = user_repository::user_get_by_recovery_key(
-            &self.database_connection,
-            recovery_data.recovery_key,
+        self.client_rate_limiter.check(client)?;
+
+        let prefix = User::recovery_key_prefix(&recovery_data.recovery_key)?;
+        self.recovery_rate_limiter.check(&prefix)?;
+
+        let user =
+            user_repository::user_get_by_recovery_key_prefix(&self.database_connection, &prefix)
+                .await;
+        let user = match user {
+            Ok(user) => user,
+            Err(ApplicationError::NotFound) => {
+                secret_hash::verify_dummy(&recovery_data.recovery_key).await;
+                return Err(ApplicationError::Unauthorized);
+            }
+            Err(e) => return Err(e),
+        };
+        if !user.verify_recovery_key(&recovery_data.recovery_key).await || user.is_banned {
+            return Err(ApplicationError::Unauthorized);
+        }
+        let (token, refresh_token) = self.create_session_token(user).await?;
+        Ok(RecoveryUserResponse {
+            token,
+            refresh_token,
+        })
+    }
+
+    pub async fn exchange_legacy_token(
+        &self,
+        data: ExchangeLegacyTokenRequest,
+    ) -> Result<ExchangeLegacyTokenResponse, ApplicationError> {
+        let user_id = User::legacy_token_user_id(&data.legacy_token, &self.config)?;
+        let user = user_repository::user_get_by_id(&self.database_connection, &user_id)
+            .await
+            .map_err(|e| match e {
+                ApplicationError::NotFound => ApplicationError::Unauthorized,
+                e => e,
+            })?;
+        if user.is_banned {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let mut tx = self.database_connection.begin().await?;
+        session_repository::legacy_token_exchange_add(
+            &mut tx,
+            &RefreshToken::hash(&data.legacy_token),
+            &user.id,
         )
         .await?;
-        let token = user.generate_token(&self.config)?;
-        Ok(RecoveryUserResponse { token })
+        let (token, refresh_token) = self.add_session_token(&mut tx, user).await?;
+        commit(tx).await?;
+        Ok(ExchangeLegacyTokenResponse {
+            token,
+            refresh_token,
+        })
+    }
+
+    pub async fn list_users(
+        &self,
+        filters: ListUsersRequest,
+        user: &User,
+    ) -> Result<ListUsersResponse, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let page = user_repository::user_search(&self.database_connection, filters).await?;
+        Ok(ListUsersResponse {
+            users: page.items.into_iter().map(|user| user.into()).collect(),
+            next_cursor: page.next_cursor,
+        })
+    }
+
+    pub async fn set_user_role(
+        &self,
+        data: SetUserRoleRequest,
+        user: &User,
+    ) -> Result<SetUserRoleResponse, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let target =
+            user_repository::user_get_by_id(&self.database_connection, &data.user_id).await?;
+        let before = AuditEvent::snapshot(&target)?;
+        let target = target.set_role(&data.role, user)?;
+        let after = AuditEvent::snapshot(&target)?;
+        let mut tx = self.database_connection.begin().await?;
+        user_repository::user_update(&mut tx, target).await?;
+        let event = AuditEvent::new(
+            user,
+            "SetUserRole",
+            EntityType::User,
+            &data.user_id,
+            before,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+        self.authentication_cache.evict_user(&data.user_id);
+        Ok(SetUserRoleResponse {})
+    }
+
+    pub async fn set_user_ban(
+        &self,
+        data: SetUserBanRequest,
+        user: &User,
+    ) -> Result<SetUserBanResponse, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let target =
+            user_repository::user_get_by_id(&self.database_connection, &data.user_id).await?;
+        let before = AuditEvent::snapshot(&target)?;
+        let target = target.set_ban(data.is_banned, user)?;
+        let after = AuditEvent::snapshot(&target)?;
+        let mut tx = self.database_connection.begin().await?;
+        user_repository::user_update(&mut tx, target).await?;
+        let event = AuditEvent::new(
+            user,
+            "SetUserBan",
+            EntityType::User,
+            &data.user_id,
+            before,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+        self.authentication_cache.evict_user(&data.user_id);
+        Ok(SetUserBanResponse {})
+    }
+
+    pub async fn rename_user(
+        &self,
+        data: RenameUserRequest,
+        user: &User,
+    ) -> Result<RenameUserResponse, ApplicationError> {
+        if user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let target =
+            user_repository::user_get_by_id(&self.database_connection, &data.user_id).await?;
+        let before = AuditEvent::snapshot(&target)?;
+        let target = target.rename(data.display_name, user)?;
+        target.validate()?;
+        let after = AuditEvent::snapshot(&target)?;
+        let mut tx = self.database_connection.begin().await?;
+        user_repository::user_update(&mut tx, target).await?;
+        let event = AuditEvent::new(
+            user,
+            "RenameUser",
+            EntityType::User,
+            data.user_id,
+            before,
+            after,
+        );
+        audit_repository::audit_event_add(&mut tx, event).await?;
+        commit(tx).await?;
+        Ok(RenameUserResponse {})
+    }
+
+    pub async fn list_sessions(
+        &self,
+        _: ListSessionsRequest,
+        user: &User,
+    ) -> Result<ListSessionsResponse, ApplicationError> {
+        let sessions =
+            session_repository::session_get_active_by_user(&self.database_connection, &user.id)
+                .await?;
+        Ok(ListSessionsResponse {
+            sessions: sessions.into_iter().map(|session| session.into()).collect(),
+        })
+    }
+
+    pub async fn revoke_session(
+        &self,
+        data: RevokeSessionRequest,
+        user: &User,
+    ) -> Result<RevokeSessionResponse, ApplicationError> {
+        let session =
+            session_repository::session_get_by_id(&self.database_connection, &data.session_id)
+                .await?
+                .revoke(user)?;
+        session_repository::session_revoke(&self.database_connection, session).await?;
+        self.authentication_cache.evict_session(&data.session_id);
+        Ok(RevokeSessionResponse {})
+    }
+
+    pub async fn revoke_all_sessions(
+        &self,
+        data: RevokeAllSessionsRequest,
+        user: &User,
+    ) -> Result<RevokeAllSessionsResponse, ApplicationError> {
+        let user_id = data.user_id.unwrap_or(user.id.clone());
+        if user_id != user.id && user.role != Roles::Admin {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        session_repository::session_revoke_all(&self.database_connection, &user_id, Utc::now())
+            .await?;
+        self.authentication_cache.evict_user(&user_id);
+        Ok(RevokeAllSessionsResponse {})
+    }
+
+    pub async fn refresh_token(
+        &self,
+        data: RefreshTokenRequest,
+    ) -> Result<RefreshTokenResponse, ApplicationError> {
+        let conn = &self.database_connection;
+        let used = refresh_token_repository::refresh_token_get_by_hash(
+            conn,
+            &RefreshToken::hash(&data.refresh_token),
+        )
+        .await
+        .map_err(|_| ApplicationError::Unauthorized)?;
+
+        if used.is_reused() {
+            let tx = self.database_connection.begin().await?;
+            self.revoke_token_family(tx, &used.session_id).await?;
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        if used.is_expired() {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let mut tx = self.database_connection.begin().await?;
+        let session = session_repository::session_get_for_update(&mut tx, &used.session_id).await?;
+        if !session.is_active() {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let user = user_repository::user_get_by_id(conn, &session.user_id).await?;
+        if user.is_banned {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let (replacement, refresh_token) = RefreshToken::new(&session);
+        if !refresh_token_repository::refresh_token_rotate(&mut tx, &used, &replacement).await? {
+            self.revoke_token_family(tx, &used.session_id).await?;
+            return Err(ApplicationError::Unauthorized);
+        }
+        commit(tx).await?;
+
+        let token = user.generate_token(&session, &self.config)?;
+        Ok(RefreshTokenResponse {
+            token,
+            refresh_token,
+        })
+    }
+
+    pub async fn update_profile(
+        &self,
+        data: UpdateProfileRequest,
+        user: &User,
+    ) -> Result<UpdateProfileResponse, ApplicationError> {
+        let updated = user
+            .clone()
+            .update_profile(data.mal_profile, data.anilist_profile)?;
+        let response = UpdateProfileResponse {
+            mal_profile: updated.mal_profile.clone(),
+            anilist_profile: updated.anilist_profile.clone(),
+        };
+        user_repository::user_update_profile(&self.database_connection, updated).await?;
+        self.authentication_cache.evict_user(&user.id);
+        Ok(response)
+    }
+
+    pub async fn link_credentials(
+        &self,
+        data: LinkCredentialsRequest,
+        user: &User,
+    ) -> Result<LinkCredentialsResponse, ApplicationError> {
+        let credentials = Credentials::new(user, &data.email, &data.password).await?;
+        credentials_repository::credentials_add(&self.database_connection, credentials).await?;
+        Ok(LinkCredentialsResponse {})
+    }
+
+    pub async fn login(
+        &self,
+        data: LoginRequest,
+        client: &str,
+    ) -> Result<LoginResponse, ApplicationError> {
+        self.client_rate_limiter.check(client)?;
+
+        let email = normalize_email(&data.email);
+        self.login_rate_limiter.check(&email)?;
+
+        let credentials =
+            credentials_repository::credentials_get_by_email(&self.database_connection, &email)
+                .await;
+        let credentials = match credentials {
+            Ok(credentials) => credentials,
+            Err(ApplicationError::NotFound) => {
+                secret_hash::verify_dummy(&data.password).await;
+                return Err(ApplicationError::Unauthorized);
+            }
+            Err(e) => return Err(e),
+        };
+        if !credentials.verify_password(&data.password).await {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let user = user_repository::user_get_by_id(&self.database_connection, &credentials.user_id)
+            .await?;
+        if user.is_banned {
+            return Err(ApplicationError::Unauthorized);
+        }
+
+        let (token, refresh_token) = self.create_session_token(user).await?;
+        Ok(LoginResponse {
+            token,
+            refresh_token,
+        })
+    }
+
+    pub async fn change_password(
+        &self,
+        data: ChangePasswordRequest,
+        user: &User,
+    ) -> Result<ChangePasswordResponse, ApplicationError> {
+        let credentials =
+            credentials_repository::credentials_get_by_user(&self.database_connection, &user.id)
+                .await?
+                .change_password(&data.current_password, &data.new_password)
+                .await?;
+        credentials_repository::credentials_update_password(&self.database_connection, credentials)
+            .await?;
+
+        if let Some(session_id) = &user.session_id {
+            session_repository::session_revoke_others(
+                &self.database_connection,
+                &user.id,
+                session_id,
+                Utc::now(),
+            )
+            .await?;
+            self.authentication_cache.evict_user(&user.id);
+        }
+
+        Ok(ChangePasswordResponse {})
+    }
+
+    async fn create_session_token(&self, user: User) -> Result<(String, String), ApplicationError> {
+        let mut tx = self.database_connection.begin().await?;
+        let tokens = self.add_session_token(&mut tx, user).await?;
+        commit(tx).await?;
+        Ok(tokens)
+    }
+
+    async fn add_session_token(
+        &self,
+        tx: &mut Transaction<'_, MySql>,
+        user: User,
+    ) -> Result<(String, String), ApplicationError> {
+        let session = Session::new(&user, &self.config);
+        session_repository::session_add(tx, &session).await?;
+        let (stored, refresh_token) = RefreshToken::new(&session);
+        refresh_token_repository::refresh_token_add(tx, &stored).await?;
+        let token = user.generate_token(&session, &self.config)?;
+        Ok((token, refresh_token))
+    }
+
+    async fn revoke_token_family(
+        &self,
+        mut tx: Transaction<'_, MySql>,
+        session_id: &str,
+    ) -> Result<(), ApplicationError> {
+        refresh_token_repository::refresh_token_revoke_family(&mut tx, session_id, Utc::now())
+            .await?;
+        commit(tx).await?;
+        self.authentication_cache.evict_session(session_id);
+        Ok(())
     }
 }
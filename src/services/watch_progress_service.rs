@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use crate::arkalis_service::{
+    GetContinueWatchingRequest, GetContinueWatchingResponse, MarkSeasonWatchedRequest,
+    MarkSeasonWatchedResponse, ReportWatchProgressRequest, ReportWatchProgressResponse,
+};
+use crate::models::error::ApplicationError;
+use crate::models::page;
+use crate::models::user::User;
+use crate::models::watch_progress::WatchProgress;
+use crate::repositories::{
+    episode_repository, season_repository, watch_progress_repository, DatabaseConnection,
+};
+
+pub struct WatchProgressService {
+    pub database_connection: Arc<DatabaseConnection>,
+}
+
+impl WatchProgressService {
+    pub async fn report_progress(
+        &self,
+        data: ReportWatchProgressRequest,
+        user: &User,
+    ) -> Result<ReportWatchProgressResponse, ApplicationError> {
+        let episode = episode_repository::episode_get_visible_by_id(
+            &self.database_connection,
+            &data.episode_id,
+            user.has_uploader_or_adm_role(),
+        )
+        .await?;
+
+        let progress = WatchProgress::new(user, &episode, data.position_seconds, data.completed);
+        watch_progress_repository::watch_progress_upsert(&self.database_connection, &progress)
+            .await?;
+        Ok(ReportWatchProgressResponse {
+            progress: Some(progress.into()),
+        })
+    }
+
+    pub async fn continue_watching(
+        &self,
+        data: GetContinueWatchingRequest,
+        user: &User,
+    ) -> Result<GetContinueWatchingResponse, ApplicationError> {
+        let entries = watch_progress_repository::watch_progress_continue_watching(
+            &self.database_connection,
+            &user.id,
+            page::page_size(data.page_size),
+        )
+        .await?;
+        Ok(GetContinueWatchingResponse {
+            entries: entries.into_iter().map(|entry| entry.into()).collect(),
+        })
+    }
+
+    pub async fn mark_season_watched(
+        &self,
+        data: MarkSeasonWatchedRequest,
+        user: &User,
+    ) -> Result<MarkSeasonWatchedResponse, ApplicationError> {
+        season_repository::season_bu_id(&self.database_connection, data.season_id).await?;
+        watch_progress_repository::watch_progress_complete_season(
+            &self.database_connection,
+            &user.id,
+            data.season_id,
+            data.source_id,
+        )
+        .await?;
+        Ok(MarkSeasonWatchedResponse {})
+    }
+}
@@ -0,0 +1,75 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum AniListExport {
+    Query { data: AniListData },
+    Collection(AniListCollection),
+}
+
+#[derive(Deserialize)]
+pub struct AniListData {
+    #[serde(rename = "MediaListCollection")]
+    pub media_list_collection: AniListCollection,
+}
+
+#[derive(Deserialize)]
+pub struct AniListCollection {
+    pub user: Option<AniListUser>,
+    #[serde(default)]
+    pub lists: Vec<AniListList>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AniListUser {
+    pub media_list_options: Option<AniListMediaListOptions>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AniListMediaListOptions {
+    pub score_format: Option<AniListScoreFormat>,
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+pub enum AniListScoreFormat {
+    #[serde(rename = "POINT_100")]
+    Point100,
+    #[serde(rename = "POINT_10_DECIMAL")]
+    Point10Decimal,
+    #[default]
+    #[serde(rename = "POINT_10")]
+    Point10,
+    #[serde(rename = "POINT_5")]
+    Point5,
+    #[serde(rename = "POINT_3")]
+    Point3,
+}
+
+#[derive(Deserialize)]
+pub struct AniListList {
+    #[serde(default)]
+    pub entries: Vec<AniListEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AniListEntry {
+    pub media_id: u64,
+    pub status: String,
+    pub score: Option<f64>,
+    pub progress: Option<u32>,
+    pub media: Option<AniListMedia>,
+}
+
+#[derive(Deserialize)]
+pub struct AniListMedia {
+    pub title: Option<AniListTitle>,
+}
+
+#[derive(Deserialize)]
+pub struct AniListTitle {
+    pub romaji: Option<String>,
+    pub english: Option<String>,
+}
@@ -0,0 +1,45 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct AniListMediaResponse {
+    pub data: Option<AniListMediaData>,
+    pub errors: Option<Vec<AniListError>>,
+}
+
+#[derive(Deserialize)]
+pub struct AniListError {
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AniListMediaData {
+    pub media: Option<AniListMedia>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AniListMedia {
+    pub id: u64,
+    pub id_mal: Option<u64>,
+    pub title: AniListTitle,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    pub is_adult: Option<bool>,
+    pub start_date: Option<AniListDate>,
+}
+
+#[derive(Deserialize)]
+pub struct AniListTitle {
+    pub romaji: Option<String>,
+    pub english: Option<String>,
+    pub native: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AniListDate {
+    pub year: Option<i32>,
+    pub month: Option<u32>,
+    pub day: Option<u32>,
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct JsonRpcResponse<T> {
+    pub result: Option<T>,
+    pub error: Option<JsonRpcError>,
+}
+
+#[derive(Deserialize)]
+pub struct JsonRpcError {
+    pub message: String,
+}
+
+pub type ResolveResult = HashMap<String, Claim>;
+
+#[derive(Deserialize)]
+pub struct Claim {
+    pub error: Option<ClaimError>,
+    pub name: Option<String>,
+    pub claim_id: Option<String>,
+    pub canonical_url: Option<String>,
+    pub timestamp: Option<i64>,
+    pub value: Option<ClaimValue>,
+}
+
+#[derive(Deserialize)]
+pub struct ClaimError {
+    pub name: Option<String>,
+    pub text: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ClaimValue {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub release_time: Option<String>,
+    pub thumbnail: Option<ClaimThumbnail>,
+    pub video: Option<ClaimVideo>,
+}
+
+#[derive(Deserialize)]
+pub struct ClaimThumbnail {
+    pub url: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ClaimVideo {
+    pub duration: Option<i64>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct GetResult {
+    pub streaming_url: Option<String>,
+    pub error: Option<String>,
+}
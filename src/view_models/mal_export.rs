@@ -0,0 +1,16 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct MalExport {
+    #[serde(default)]
+    pub anime: Vec<MalAnime>,
+}
+
+#[derive(Deserialize)]
+pub struct MalAnime {
+    pub series_animedb_id: u64,
+    pub series_title: Option<String>,
+    pub my_watched_episodes: Option<u32>,
+    pub my_score: Option<u8>,
+    pub my_status: String,
+}
@@ -1 +1,5 @@
+pub mod anilist_export;
+pub mod anilist_media;
+pub mod lbry;
+pub mod mal_export;
 pub mod odysee;